@@ -0,0 +1,119 @@
+//! Measures frame time with a steady stream of projectiles.
+//!
+//! A grid of pistols fires every frame through the game's own weapon and
+//! projectile plugins, once with [`PooledAttacks`] recycling projectiles
+//! through the entity pool and once spawning and despawning them.
+//!
+//! Run with `cargo bench --bench projectile_pool`.
+
+#![allow(clippy::type_complexity)]
+
+use avian2d::prelude::*;
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use bevy_rand::prelude::{EntropyPlugin, WyRand};
+use slash::{
+    faction::FactionRelations,
+    pool::{self, Inactive},
+    projectile::{self, Lifetime, Projectile},
+    weapon::{
+        self, AttackCooldown, Magazine, Pistol, PooledAttacks, ReserveAmmo, TriggerWeapon, Weapon,
+    },
+};
+use std::time::{Duration, Instant};
+
+const TIMESTEP: f64 = 1.0 / 64.0;
+/// Pistols laid out in a grid, each firing every frame.
+const WEAPONS: usize = 200;
+/// Frames a projectile lives before its [`Lifetime`] expires.
+const LIFETIME_FRAMES: usize = 10;
+const WARMUP_FRAMES: usize = 60;
+const FRAMES: usize = 600;
+
+#[derive(Clone, Copy, Resource)]
+enum Mode {
+    Pooled,
+    Spawned,
+}
+
+fn main() {
+    for mode in [Mode::Pooled, Mode::Spawned] {
+        let mut app = App::new();
+        app.add_plugins((
+            MinimalPlugins,
+            TransformPlugin,
+            AssetPlugin::default(),
+            PhysicsPlugins::default().with_length_unit(2.0),
+            EntropyPlugin::<WyRand>::with_seed(69u64.to_le_bytes()),
+            bevy_tween::DefaultTweenPlugins,
+            pool::plugin,
+            projectile::plugin,
+            weapon::plugin,
+        ))
+        .init_resource::<FactionRelations>()
+        .insert_resource(Gravity(Vec2::ZERO))
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            TIMESTEP,
+        )))
+        .insert_resource(mode)
+        .add_systems(Startup, spawn_weapons)
+        .add_systems(Update, fire_weapons);
+        app.finish();
+        app.cleanup();
+
+        for _ in 0..WARMUP_FRAMES {
+            app.update();
+        }
+
+        let mut frame_times = Vec::with_capacity(FRAMES);
+        let mut live = 0;
+        for _ in 0..FRAMES {
+            let start = Instant::now();
+            app.update();
+            frame_times.push(start.elapsed());
+            live += app
+                .world_mut()
+                .query_filtered::<(), (With<Projectile>, Without<Inactive>)>()
+                .iter(app.world())
+                .count();
+        }
+        report(mode, &mut frame_times, live / FRAMES);
+    }
+}
+
+fn report(mode: Mode, frame_times: &mut [Duration], live: usize) {
+    frame_times.sort_unstable();
+    let mean = frame_times.iter().sum::<Duration>() / frame_times.len() as u32;
+    let p50 = frame_times[frame_times.len() / 2];
+    let p99 = frame_times[frame_times.len() * 99 / 100];
+    let name = match mode {
+        Mode::Pooled => "pooled",
+        Mode::Spawned => "spawned",
+    };
+    println!(
+        "{name:>8}: ~{live} live projectiles, {WEAPONS} fired per frame | \
+         mean {mean:?} | p50 {p50:?} | p99 {p99:?}"
+    );
+}
+
+fn spawn_weapons(mut commands: Commands, mode: Res<Mode>) {
+    for i in 0..WEAPONS {
+        let x = (i % 20) as f32 * 60.0 - 570.0;
+        let y = (i / 20) as f32 * 60.0 - 270.0;
+        let mut weapon = commands.spawn((
+            Pistol,
+            AttackCooldown::from_seconds(0.0),
+            Lifetime::from_seconds((LIFETIME_FRAMES as f64 * TIMESTEP) as f32),
+            Transform::from_xyz(x, y, 0.0).with_rotation(Quat::from_rotation_z(i as f32)),
+        ));
+        weapon.remove::<(Magazine, ReserveAmmo)>();
+        if let Mode::Spawned = *mode {
+            weapon.remove::<PooledAttacks>();
+        }
+    }
+}
+
+fn fire_weapons(mut commands: Commands, weapons: Query<Entity, With<Weapon>>) {
+    for weapon in weapons.iter() {
+        commands.trigger(TriggerWeapon::friendly(weapon));
+    }
+}
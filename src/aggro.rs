@@ -0,0 +1,262 @@
+use crate::{
+    Layer, enemy::SteerTarget, faction::FactionSystems, navigation::NavigationSystems,
+    weapon::HitEvent,
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_rand::{global::GlobalRng, prelude::WyRand};
+use rand::Rng;
+use std::f32::consts::TAU;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedPostUpdate,
+        (update_line_of_sight, update_awareness, patrol)
+            .chain()
+            .after(FactionSystems::Target)
+            .before(NavigationSystems::Plan),
+    )
+    .add_observer(alert_on_hit);
+}
+
+/// Distance from its post that a patrolling entity wanders.
+const PATROL_RADIUS: f32 = 80.0;
+
+/// Distance at which a patrol waypoint counts as reached.
+const WAYPOINT_RADIUS: f32 = 8.0;
+
+/// Seconds a patrolling entity waits at each waypoint.
+const PATROL_WAIT: f32 = 2.0;
+
+/// Seconds before giving up on a waypoint, such as one placed inside a wall.
+const WAYPOINT_TIMEOUT: f32 = 5.0;
+
+/// Detects the [`SteerTarget`] within `radius` while in [`LineOfSight`].
+///
+/// Once out of sight, the target's last seen position is remembered for `memory` seconds.
+#[derive(Component)]
+#[require(Awareness, LineOfSight, Patrol)]
+pub struct Aggro {
+    pub radius: f32,
+    pub memory: f32,
+}
+
+impl Default for Aggro {
+    fn default() -> Self {
+        Self {
+            radius: 250.0,
+            memory: 4.0,
+        }
+    }
+}
+
+/// Whether the [`SteerTarget`] can be seen without a [`Layer::Wall`] in the way.
+#[derive(Default, Component)]
+pub struct LineOfSight(pub bool);
+
+/// What an entity knows about its [`SteerTarget`].
+#[derive(Default, Component)]
+pub enum Awareness {
+    /// The target has not been detected, so the entity [`Patrol`]s.
+    #[default]
+    Idle,
+    /// The target is within the aggro radius and in sight.
+    Engaged,
+    /// The target was lost, and is searched for where it was last seen.
+    Searching { last_seen: Vec2, memory: Timer },
+}
+
+impl Awareness {
+    pub fn is_engaged(&self) -> bool {
+        matches!(self, Self::Engaged)
+    }
+}
+
+/// Wanders between random waypoints around a post while [`Awareness::Idle`].
+///
+/// The post is the entity's position when it first patrols, or where it gave up searching.
+#[derive(Component)]
+pub struct Patrol {
+    post: Option<Vec2>,
+    waypoint: Option<Vec2>,
+    wait: Timer,
+}
+
+impl Default for Patrol {
+    fn default() -> Self {
+        Self {
+            post: None,
+            waypoint: None,
+            wait: Timer::from_seconds(PATROL_WAIT, TimerMode::Once),
+        }
+    }
+}
+
+impl Patrol {
+    /// Direction toward the current waypoint, or zero while waiting.
+    pub fn direction(&self, translation: Vec2) -> Vec2 {
+        self.waypoint
+            .map(|waypoint| (waypoint - translation).normalize_or_zero())
+            .unwrap_or_default()
+    }
+}
+
+fn update_line_of_sight(
+    spatial_query: SpatialQuery,
+    mut seers: Query<(&mut LineOfSight, &GlobalTransform, &SteerTarget)>,
+    targets: Query<&GlobalTransform>,
+) {
+    let filter = SpatialQueryFilter::from_mask(Layer::Wall);
+    for (mut line_of_sight, transform, steer_target) in seers.iter_mut() {
+        let Ok(target) = targets.get(steer_target.0) else {
+            line_of_sight.0 = false;
+            continue;
+        };
+        let origin = transform.translation().xy();
+        let diff = target.translation().xy() - origin;
+        line_of_sight.0 = match Dir2::new(diff) {
+            Ok(direction) => spatial_query
+                .cast_ray(origin, direction, diff.length(), true, &filter)
+                .is_none(),
+            Err(_) => true,
+        };
+    }
+}
+
+fn update_awareness(
+    time: Res<Time>,
+    mut seers: Query<(
+        &Aggro,
+        &mut Awareness,
+        &mut Patrol,
+        &LineOfSight,
+        &GlobalTransform,
+        &SteerTarget,
+    )>,
+    targets: Query<&GlobalTransform>,
+) {
+    for (aggro, mut awareness, mut patrol, line_of_sight, transform, steer_target) in
+        seers.iter_mut()
+    {
+        let translation = transform.translation().xy();
+        let target = targets
+            .get(steer_target.0)
+            .ok()
+            .map(|target| target.translation().xy());
+        let seen = target.filter(|target| {
+            line_of_sight.0 && target.distance_squared(translation) <= aggro.radius * aggro.radius
+        });
+
+        match (&mut *awareness, seen) {
+            (_, Some(_)) => *awareness = Awareness::Engaged,
+            (Awareness::Engaged, None) => {
+                *awareness = Awareness::Searching {
+                    last_seen: target.unwrap_or(translation),
+                    memory: Timer::from_seconds(aggro.memory, TimerMode::Once),
+                };
+            }
+            (Awareness::Searching { memory, .. }, None) => {
+                if memory.tick(time.delta()).is_finished() {
+                    *awareness = Awareness::Idle;
+                    patrol.post = Some(translation);
+                    patrol.waypoint = None;
+                }
+            }
+            (Awareness::Idle, None) => {}
+        }
+    }
+}
+
+fn patrol(
+    time: Res<Time>,
+    mut patrols: Query<(&mut Patrol, &Awareness, &GlobalTransform)>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    for (mut patrol, awareness, transform) in patrols.iter_mut() {
+        let translation = transform.translation().xy();
+        let post = *patrol.post.get_or_insert(translation);
+        if !matches!(awareness, Awareness::Idle) {
+            continue;
+        }
+
+        let finished = patrol.wait.tick(time.delta()).is_finished();
+        match patrol.waypoint {
+            Some(waypoint) if finished || waypoint.distance(translation) <= WAYPOINT_RADIUS => {
+                patrol.waypoint = None;
+                patrol.wait = Timer::from_seconds(PATROL_WAIT, TimerMode::Once);
+            }
+            None if finished => {
+                let angle = rng.random_range(0.0..TAU);
+                let distance = rng.random_range(0.0..PATROL_RADIUS);
+                patrol.waypoint = Some(post + Vec2::from_angle(angle) * distance);
+                patrol.wait = Timer::from_seconds(WAYPOINT_TIMEOUT, TimerMode::Once);
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Idle entities that are hit by a wielded attack start searching where it came from.
+///
+/// Hits without a [`HitEvent::wielder`], such as status effects, go unnoticed.
+fn alert_on_hit(hit: On<HitEvent>, mut awareness: Query<(&Aggro, &mut Awareness)>) {
+    if hit.wielder.is_none() {
+        return;
+    }
+    if let Ok((aggro, mut awareness)) = awareness.get_mut(hit.target)
+        && matches!(*awareness, Awareness::Idle)
+    {
+        *awareness = Awareness::Searching {
+            last_seen: hit.attacker_translation,
+            memory: Timer::from_seconds(aggro.memory, TimerMode::Once),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bits::BitSource, pool::EntityPools, weapon};
+
+    fn hit(target: Entity, attacker: Entity, wielder: Option<Entity>) -> HitEvent {
+        HitEvent {
+            target,
+            attacker: Some(attacker),
+            wielder,
+            damage: 1.0,
+            knockback: Vec2::ZERO,
+            bits: 0,
+            source: BitSource::Wild,
+            target_translation: Vec2::ZERO,
+            attacker_translation: Vec2::new(10.0, 0.0),
+            crit: false,
+        }
+    }
+
+    #[test]
+    fn only_wielded_hits_alert_through_hurtboxes() {
+        let mut world = World::new();
+        world.init_resource::<EntityPools>();
+        world.add_observer(weapon::handle_attack);
+        world.add_observer(alert_on_hit);
+
+        let root = world.spawn(Aggro::default()).id();
+        let hurtbox = world.spawn(ChildOf(root)).id();
+        let attack = world.spawn_empty().id();
+        let wielder = world.spawn_empty().id();
+
+        world.trigger(hit(hurtbox, attack, None));
+        world.flush();
+        assert!(matches!(
+            world.get::<Awareness>(root),
+            Some(Awareness::Idle)
+        ));
+
+        world.trigger(hit(hurtbox, attack, Some(wielder)));
+        world.flush();
+        assert!(matches!(
+            world.get::<Awareness>(root),
+            Some(Awareness::Searching { last_seen, .. }) if *last_seen == Vec2::new(10.0, 0.0)
+        ));
+    }
+}
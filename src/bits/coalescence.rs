@@ -1,9 +1,16 @@
 use avian2d::prelude::*;
 use bevy::{color::palettes::css::YELLOW, ecs::entity::EntityHashSet, prelude::*};
+use bevy_rand::{global::GlobalRng, prelude::WyRand};
 use rand::Rng;
 use std::time::Duration;
 
-use crate::bits::Bit;
+use crate::{
+    bits::{Bit, BitComposition, BitSource},
+    health::{CurrentHealth, MaxHealth},
+    player::stats::{Stat, Stats},
+    pool::{EntityPool, Inactive},
+    run::{RunStats, Wallet},
+};
 
 pub struct CoalescencePlugin;
 
@@ -13,25 +20,68 @@ impl Plugin for CoalescencePlugin {
             FixedPostUpdate,
             (
                 CoalesceTimer::manage_timers,
+                assign_absorbers,
                 // coalesce,
                 absorb,
+                collect_bits,
                 apply_mass,
                 apply_absorption_forces,
                 // attraction,
             )
                 .chain()
+                .in_set(CoalescenceSystems::Consume)
                 .in_set(PhysicsSystems::Last),
         )
-        .add_observer(BitMass::insert)
-        .add_observer(assign_absorber);
+        .init_resource::<ConsumedBits>()
+        .add_observer(BitMass::insert);
     }
 }
 
-const MASS_THRESOLD: f32 = 30.0;
+/// Orders coalescence systems in the `FixedPostUpdate` schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum CoalescenceSystems {
+    /// Bits are absorbed and collected, and marked in [`ConsumedBits`].
+    Consume,
+}
+
+pub const MASS_THRESOLD: f32 = 30.0;
 
-/// Fired when a bit exceeds the mass threshold.
+/// Bits consumed during the current tick, whose release to the pool is still deferred.
+///
+/// Cleared by [`absorb`], the first system to consume bits each tick, and
+/// shared with [`decay`](super::decay) so that no bit is counted twice.
+#[derive(Default, Resource)]
+pub struct ConsumedBits(EntityHashSet);
+
+impl ConsumedBits {
+    /// Marks `bit` as consumed, returning `false` if it already was.
+    pub fn consume(&mut self, bit: Entity) -> bool {
+        self.0.insert(bit)
+    }
+
+    pub fn contains(&self, bit: Entity) -> bool {
+        self.0.contains(&bit)
+    }
+}
+
+/// Fired when a bit or an [`EnemyAbsorber`] exceeds the mass threshold.
 #[derive(EntityEvent)]
-pub struct CoalesceEvent(pub Entity);
+pub struct CoalesceEvent {
+    pub entity: Entity,
+    /// The bits that coalesced, which decide the enemy's archetype.
+    ///
+    /// Empty when the coalescence was not caused by bits.
+    pub composition: BitComposition,
+}
+
+impl CoalesceEvent {
+    pub fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            composition: BitComposition::default(),
+        }
+    }
+}
 
 /// We'll wait a moment to start checking for coalescence.
 #[derive(Component)]
@@ -74,17 +124,28 @@ pub struct BitMass(pub f32);
 impl BitMass {
     fn insert(
         trigger: On<Insert, Self>,
-        mut target: Query<(&BitMass, &mut Transform)>,
+        mut target: Query<(
+            &BitMass,
+            &mut Transform,
+            Option<&BitSource>,
+            Option<&BitComposition>,
+        )>,
         mut commands: Commands,
     ) -> Result {
-        let (mass, mut transform) = target.get_mut(trigger.entity)?;
+        let (mass, mut transform, source, composition) = target.get_mut(trigger.entity)?;
 
         // for now, it's just 1-to-1
         commands.entity(trigger.entity).insert(Mass(mass.0));
         transform.scale = Vec3::splat(1.0 + (1.0 + mass.0).log10());
 
         if mass.0 >= MASS_THRESOLD {
-            commands.entity(trigger.entity).trigger(CoalesceEvent);
+            let composition = BitComposition::of(mass.0, source, composition);
+            commands
+                .entity(trigger.entity)
+                .trigger(|entity| CoalesceEvent {
+                    entity,
+                    composition,
+                });
         }
 
         Ok(())
@@ -92,7 +153,7 @@ impl BitMass {
 }
 
 #[derive(Component, Default)]
-struct TempMass(f32);
+pub struct TempMass(f32);
 
 #[allow(unused)]
 fn coalesce(
@@ -189,25 +250,48 @@ pub struct AbsorbeeOf(pub Entity);
 #[relationship_target(relationship = AbsorbeeOf)]
 pub struct Absorbees(Vec<Entity>);
 
-/// This ensures each absorber receives (on average) an exact share of the total bits.
-fn assign_absorber(
-    trigger: On<Insert, Bit>,
-    absorbers: Query<Entity, With<Absorber>>,
+/// Picks an absorber or [`BitMagnet`] for each unassigned bit, weighted by their `weight`.
+///
+/// On average, each receives a share of the total bits proportional to its weight.
+/// Bits wait unassigned while there is nothing to absorb them, and are assigned
+/// again if their absorber is destroyed.
+fn assign_absorbers(
+    bits: Query<Entity, (With<Bit>, Without<AbsorbeeOf>, Without<Inactive>)>,
+    absorbers: Query<(Entity, &Absorber)>,
+    magnets: Query<(Entity, &BitMagnet)>,
     mut commands: Commands,
-) -> Result {
-    let total = absorbers.iter().len();
-    let selection: usize = rand::rng().random_range(0..total);
-
-    let selection = absorbers
-        .iter()
-        .nth(selection)
-        .ok_or("Expected at least `nth` absorbers")?;
-
-    commands
-        .entity(trigger.entity)
-        .insert(AbsorbeeOf(selection));
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) {
+    let candidates = || {
+        absorbers
+            .iter()
+            .map(|(entity, absorber)| (entity, absorber.weight))
+            .chain(
+                magnets
+                    .iter()
+                    .map(|(entity, magnet)| (entity, magnet.weight)),
+            )
+            .filter(|(_, weight)| *weight > 0.0)
+    };
+    let total: f32 = candidates().map(|(_, weight)| weight).sum();
+    if total <= 0.0 {
+        return;
+    }
 
-    Ok(())
+    for bit in &bits {
+        let mut selection = rng.random_range(0.0..total);
+        let selection = candidates()
+            .find(|(_, weight)| {
+                let found = selection < *weight;
+                selection -= weight;
+                found
+            })
+            .or_else(|| candidates().last());
+
+        if let Some((selection, _)) = selection {
+            commands.entity(bit).insert(AbsorbeeOf(selection));
+        }
+    }
 }
 
 #[derive(Component)]
@@ -219,6 +303,10 @@ fn assign_absorber(
 pub struct Absorber {
     pub mass: f32,
     pub bits_absorbed: f32,
+    /// Where the bits that make up `bits_absorbed` came from.
+    pub composition: BitComposition,
+    /// Relative chance that new bits are assigned to this absorber.
+    pub weight: f32,
 }
 
 impl Absorber {
@@ -226,6 +314,35 @@ impl Absorber {
         Self {
             mass,
             bits_absorbed: 0.0,
+            composition: BitComposition::default(),
+            weight: 1.0,
+        }
+    }
+
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+}
+
+/// Pulls assigned bits within `radius` and collects any bit within
+/// `pickup_radius` into the [`Wallet`].
+#[derive(Component)]
+pub struct BitMagnet {
+    pub radius: f32,
+    pub pickup_radius: f32,
+    pub mass: f32,
+    /// Relative chance that new bits are assigned to this magnet over absorbers.
+    pub weight: f32,
+}
+
+impl Default for BitMagnet {
+    fn default() -> Self {
+        Self {
+            radius: 120.0,
+            pickup_radius: 15.0,
+            mass: 10.0,
+            weight: 1.0,
         }
     }
 }
@@ -236,14 +353,24 @@ pub struct EnemyAbsorber;
 fn apply_absorption_forces(
     mut bits: Query<(&Position, Forces, &BitMass, &AbsorbeeOf), Without<CoalesceTimer>>,
     absorbers: Query<(&Absorber, &Position)>,
+    magnets: Query<(&BitMagnet, &Position)>,
 ) -> Result {
     for (position, mut forces, mass, absorber) in &mut bits {
         let mut impulse = Vec2::ZERO;
 
-        let (other_mass, other_pos) = absorbers.get(absorber.0)?;
+        let (other_mass, other_pos) = match absorbers.get(absorber.0) {
+            Ok((absorber, other_pos)) => (absorber.mass, other_pos),
+            Err(_) => {
+                let (magnet, other_pos) = magnets.get(absorber.0)?;
+                if other_pos.distance(position.0) > magnet.radius {
+                    continue;
+                }
+                (magnet.mass, other_pos)
+            }
+        };
 
         // gmm/r^2
-        let mass = mass.0 * other_mass.mass;
+        let mass = mass.0 * other_mass;
         let direction = (other_pos.0 - position.0).normalize_or_zero();
         let distance = other_pos.distance(position.0);
         let force = mass / distance.max(0.01);
@@ -258,12 +385,21 @@ fn apply_absorption_forces(
 
 fn absorb(
     mut absorbers: Query<(Entity, &mut Absorber, Has<EnemyAbsorber>)>,
-    bits: Query<(Entity, &BitMass)>,
+    bits: Query<(
+        Entity,
+        &BitMass,
+        Option<&BitSource>,
+        Option<&BitComposition>,
+    )>,
     collisions: Collisions,
     mut commands: Commands,
+    mut run_stats: ResMut<RunStats>,
+    mut consumed: ResMut<ConsumedBits>,
+    mut pool: EntityPool,
 ) {
+    consumed.0.clear();
     for (absorber_entity, mut absorber, is_enemy_absorber) in &mut absorbers {
-        let bits_absorbed = &mut absorber.bits_absorbed;
+        let absorber = &mut *absorber;
 
         for contact_pair in collisions.collisions_with(absorber_entity) {
             if !contact_pair.is_touching() {
@@ -276,19 +412,67 @@ fn absorb(
                 contact_pair.collider1
             };
 
-            let Ok((_, &BitMass(other_mass))) = bits.get(other) else {
+            let Ok((_, &BitMass(other_mass), source, composition)) = bits.get(other) else {
                 continue;
             };
+            // Touching more than one absorber.
+            if !consumed.consume(other) {
+                continue;
+            }
 
-            *bits_absorbed += other_mass;
-            commands.entity(other).despawn();
-
-            if *bits_absorbed >= MASS_THRESOLD {
-                *bits_absorbed -= MASS_THRESOLD;
+            absorber.bits_absorbed += other_mass;
+            run_stats.bits_absorbed += other_mass.round() as usize;
+            absorber
+                .composition
+                .merge(&BitComposition::of(other_mass, source, composition));
+            pool.despawn(other);
+
+            if absorber.bits_absorbed >= MASS_THRESOLD {
+                // The coalesced share of every source leaves with the enemy.
+                let mut composition = absorber.composition;
+                composition.scale(MASS_THRESOLD / absorber.bits_absorbed);
+                absorber
+                    .composition
+                    .scale(1.0 - MASS_THRESOLD / absorber.bits_absorbed);
+                absorber.bits_absorbed -= MASS_THRESOLD;
 
                 if is_enemy_absorber {
-                    commands.trigger(CoalesceEvent(absorber_entity));
+                    commands.trigger(CoalesceEvent {
+                        entity: absorber_entity,
+                        composition,
+                    });
+                }
+            }
+        }
+    }
+}
+
+fn collect_bits(
+    mut magnets: Query<(
+        &BitMagnet,
+        &Position,
+        Option<(&Stats, &mut CurrentHealth, &MaxHealth)>,
+    )>,
+    bits: Query<
+        (Entity, &BitMass, &Position),
+        (With<Bit>, Without<CoalesceTimer>, Without<Inactive>),
+    >,
+    mut wallet: ResMut<Wallet>,
+    mut run_stats: ResMut<RunStats>,
+    mut consumed: ResMut<ConsumedBits>,
+    mut pool: EntityPool,
+) {
+    for (magnet, magnet_pos, mut health) in &mut magnets {
+        for (bit, &BitMass(mass), position) in &bits {
+            if position.distance(magnet_pos.0) <= magnet.pickup_radius && consumed.consume(bit) {
+                let bits = mass.round() as usize;
+                wallet.bits += bits;
+                run_stats.bits_collected += bits;
+                if let Some((stats, current, max)) = &mut health {
+                    let heal = bits as f32 * stats.get(Stat::BitHeal);
+                    current.0 = (current.0 + heal).min(max.0);
                 }
+                pool.despawn(bit);
             }
         }
     }
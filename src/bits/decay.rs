@@ -0,0 +1,178 @@
+use avian2d::prelude::*;
+use bevy::{ecs::entity::EntityHashMap, prelude::*};
+
+use crate::{
+    bits::{
+        Bit, BitComposition, BitSource,
+        coalescence::{BitMass, CoalescenceSystems, ConsumedBits, MASS_THRESOLD},
+    },
+    pool::{EntityPool, Inactive},
+    run::RunStats,
+};
+
+pub struct DecayPlugin;
+
+impl Plugin for DecayPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<BitDecay>()
+            .add_systems(Update, fade_bits)
+            .add_systems(
+                FixedPostUpdate,
+                (expire_bits, cap_bits)
+                    .chain()
+                    .after(CoalescenceSystems::Consume)
+                    .in_set(PhysicsSystems::Last),
+            );
+    }
+}
+
+/// Configures how long bits live and how many can exist at once.
+#[derive(Debug, Resource)]
+pub struct BitDecay {
+    /// Seconds an unabsorbed bit lives, including its fade.
+    pub lifetime: f32,
+    /// Seconds over which a bit fades out before it expires.
+    pub fade: f32,
+    /// Live bits allowed before the oldest ones overflow.
+    pub cap: usize,
+    pub overflow: Overflow,
+}
+
+impl Default for BitDecay {
+    fn default() -> Self {
+        Self {
+            lifetime: 20.0,
+            fade: 3.0,
+            cap: 300,
+            overflow: Overflow::Merge,
+        }
+    }
+}
+
+/// What happens to the oldest bits once there are more than [`BitDecay::cap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overflow {
+    /// Merges each into the closest younger bit, culling it if none can take its mass.
+    Merge,
+    Cull,
+}
+
+/// Expires a bit once the timer finishes, fading it out before.
+///
+/// Inserted with the [`BitDecay::lifetime`] when a bit is spawned.
+#[derive(Component)]
+pub struct BitLifetime(Timer);
+
+impl BitLifetime {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+fn fade_bits(
+    decay: Res<BitDecay>,
+    mut bits: Query<(&mut Sprite, &BitLifetime), (With<Bit>, Without<Inactive>)>,
+) {
+    for (mut sprite, lifetime) in bits.iter_mut() {
+        let alpha = if decay.fade > 0.0 {
+            (lifetime.0.remaining_secs() / decay.fade).min(1.0)
+        } else {
+            1.0
+        };
+        sprite.color.set_alpha(alpha);
+    }
+}
+
+/// Despawns bits whose lifetime finished, unless absorbed or collected this tick.
+fn expire_bits(
+    time: Res<Time>,
+    mut bits: Query<(Entity, &mut BitLifetime, &BitMass), (With<Bit>, Without<Inactive>)>,
+    mut run_stats: ResMut<RunStats>,
+    mut consumed: ResMut<ConsumedBits>,
+    mut pool: EntityPool,
+) {
+    for (entity, mut lifetime, mass) in bits.iter_mut() {
+        if lifetime.0.tick(time.delta()).is_finished() && consumed.consume(entity) {
+            run_stats.bits_wasted += mass.0.round() as usize;
+            pool.despawn(entity);
+        }
+    }
+}
+
+/// Merges or culls the oldest bits beyond the [`BitDecay::cap`].
+///
+/// Bits already consumed this tick are neither counted nor merged into.
+fn cap_bits(
+    mut commands: Commands,
+    decay: Res<BitDecay>,
+    bits: Query<
+        (
+            Entity,
+            &BitLifetime,
+            &BitMass,
+            &Position,
+            Option<&BitSource>,
+            Option<&BitComposition>,
+        ),
+        (With<Bit>, Without<Inactive>),
+    >,
+    mut run_stats: ResMut<RunStats>,
+    mut consumed: ResMut<ConsumedBits>,
+    mut pool: EntityPool,
+) {
+    let mut live = bits
+        .iter()
+        .filter(|(entity, ..)| !consumed.contains(*entity))
+        .map(|(entity, lifetime, mass, position, source, composition)| {
+            let composition = BitComposition::of(mass.0, source, composition);
+            (
+                entity,
+                lifetime.0.elapsed_secs(),
+                mass.0,
+                position.0,
+                composition,
+            )
+        })
+        .collect::<Vec<_>>();
+    let count = live.len();
+    if count <= decay.cap {
+        return;
+    }
+
+    live.sort_by(|(_, a, ..), (_, b, ..)| b.total_cmp(a));
+    let (oldest, younger) = live.split_at(count - decay.cap);
+
+    let mut merged = EntityHashMap::<(f32, BitComposition)>::default();
+    for &(entity, _, mass, position, composition) in oldest {
+        consumed.consume(entity);
+        pool.despawn(entity);
+
+        let target = match decay.overflow {
+            Overflow::Merge => younger
+                .iter()
+                .filter(|(target, _, target_mass, ..)| {
+                    let merged_mass = merged.get(target).map_or(*target_mass, |(mass, _)| *mass);
+                    merged_mass + mass < MASS_THRESOLD
+                })
+                .min_by(|(_, _, _, a, _), (_, _, _, b, _)| {
+                    a.distance_squared(position)
+                        .total_cmp(&b.distance_squared(position))
+                }),
+            Overflow::Cull => None,
+        };
+        match target {
+            Some(&(target, _, target_mass, _, target_composition)) => {
+                let (merged_mass, merged_composition) = merged
+                    .entry(target)
+                    .or_insert((target_mass, target_composition));
+                *merged_mass += mass;
+                merged_composition.merge(&composition);
+            }
+            None => run_stats.bits_wasted += mass.round() as usize,
+        }
+    }
+
+    for (target, (mass, composition)) in merged {
+        commands.entity(target).insert((composition, BitMass(mass)));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::weapon::HitEvent;
+use crate::{
+    pool::{EntityPool, Prefab},
+    weapon::HitEvent,
+};
 use avian2d::prelude::*;
 use bevy::{color::palettes::css::GREEN, prelude::*};
 use bevy_rand::{global::GlobalRng, prelude::WyRand};
@@ -6,12 +9,13 @@ use rand::Rng;
 use std::f32::consts::PI;
 
 pub mod coalescence;
+pub mod decay;
 
 pub struct BitsPlugin;
 
 impl Plugin for BitsPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(coalescence::CoalescencePlugin)
+        app.add_plugins((coalescence::CoalescencePlugin, decay::DecayPlugin))
             .add_message::<BitEvent>()
             .add_systems(Update, handle_bit_events);
     }
@@ -34,27 +38,133 @@ const INITIAL_SIZE: f32 = 8f32;
 )]
 pub struct Bit;
 
+impl Prefab for Bit {
+    fn reset(entity: &mut EntityWorldMut) {
+        entity.remove::<(
+            coalescence::AbsorbeeOf,
+            coalescence::CoalesceTimer,
+            coalescence::TempMass,
+            BitComposition,
+        )>();
+    }
+}
+
 /// Describes the number of bits an attack will produce.
 #[derive(Default, Clone, Copy, Component)]
 pub struct BitProducer(pub usize);
 
+/// The kind of weapon that produced a bit.
+///
+/// Placed on a weapon, it is copied onto each attack, and from there onto the
+/// bits its hits produce. Absorbers coalesce enemies according to the
+/// [`BitComposition`] of the bits they collect.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Component)]
+pub enum BitSource {
+    Blade,
+    Heavy,
+    Ranged,
+    /// Produced without a weapon, such as by status effects.
+    #[default]
+    Wild,
+}
+
+impl BitSource {
+    pub const ALL: [Self; 4] = [Self::Blade, Self::Heavy, Self::Ranged, Self::Wild];
+}
+
+/// Bit mass collected from each [`BitSource`].
+///
+/// Placed on a bit that others were merged into, it takes precedence over its
+/// own [`BitSource`].
+#[derive(Debug, Default, Clone, Copy, Component)]
+pub struct BitComposition([f32; BitSource::ALL.len()]);
+
+impl BitComposition {
+    pub fn single(source: BitSource, mass: f32) -> Self {
+        let mut composition = Self::default();
+        composition.add(source, mass);
+        composition
+    }
+
+    /// The composition of a bit of `mass`, from its [`BitComposition`] if merged.
+    pub fn of(mass: f32, source: Option<&BitSource>, composition: Option<&Self>) -> Self {
+        composition
+            .copied()
+            .unwrap_or_else(|| Self::single(source.copied().unwrap_or_default(), mass))
+    }
+
+    pub fn add(&mut self, source: BitSource, mass: f32) {
+        self.0[source as usize] += mass;
+    }
+
+    pub fn merge(&mut self, other: &Self) {
+        for source in BitSource::ALL {
+            self.add(source, other.get(source));
+        }
+    }
+
+    pub fn get(&self, source: BitSource) -> f32 {
+        self.0[source as usize]
+    }
+
+    pub fn total(&self) -> f32 {
+        self.0.iter().sum()
+    }
+
+    /// Scales the mass of every source, keeping their proportions.
+    pub fn scale(&mut self, scale: f32) {
+        for mass in self.0.iter_mut() {
+            *mass *= scale;
+        }
+    }
+
+    /// Picks a source, weighted by its share of the total mass.
+    pub fn sample(&self, rng: &mut impl Rng) -> Option<BitSource> {
+        let total = self.total();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut selection = rng.random_range(0.0..total);
+        BitSource::ALL
+            .into_iter()
+            .find(|source| {
+                let found = selection < self.get(*source);
+                selection -= self.get(*source);
+                found
+            })
+            .or_else(|| {
+                BitSource::ALL
+                    .into_iter()
+                    .rfind(|source| self.get(*source) > 0.0)
+            })
+    }
+}
+
 #[derive(Message)]
 pub struct BitEvent {
     pub direction: Vec2,
     pub translation: Vec2,
     pub bits: usize,
+    pub source: BitSource,
 }
 
 fn handle_bit_events(
-    mut commands: Commands,
+    mut pool: EntityPool,
     mut reader: MessageReader<BitEvent>,
+    decay: Res<decay::BitDecay>,
     mut rng: Single<&mut WyRand, With<GlobalRng>>,
 ) {
     for event in reader.read() {
         for _ in 0..event.bits {
             let direction = random_direction_in_arc(event.direction, PI * 0.75, &mut rng);
-            commands.spawn((
+            pool.spawn::<Bit>((
                 Bit,
+                event.source,
+                coalescence::BitMass(1.0),
+                coalescence::CoalesceTimer::default(),
+                decay::BitLifetime::from_seconds(decay.lifetime),
+                ColliderDisabled,
                 Transform::from_translation(event.translation.extend(0.0)),
                 LinearVelocity(direction * BITS_SPEED * rng.random_range(0.8..1.2)),
             ));
@@ -62,11 +172,24 @@ fn handle_bit_events(
     }
 }
 
-pub fn produce_bits(hit: On<HitEvent>, mut writer: MessageWriter<BitEvent>) {
+/// Multiplies the bits produced by hits on this entity.
+#[derive(Clone, Copy, Component)]
+pub struct BitYield(pub f32);
+
+pub fn produce_bits(
+    hit: On<HitEvent>,
+    mut writer: MessageWriter<BitEvent>,
+    yields: Query<&BitYield>,
+) {
+    let bits = match yields.get(hit.target) {
+        Ok(bit_yield) => (hit.bits as f32 * bit_yield.0).round() as usize,
+        Err(_) => hit.bits,
+    };
     writer.write(BitEvent {
         direction: hit.target_translation - hit.attacker_translation,
         translation: hit.target_translation,
-        bits: hit.bits,
+        bits,
+        source: hit.source,
     });
 }
 
@@ -95,3 +218,55 @@ fn random_direction_in_arc(dir: Vec2, arc_radians: f32, rng: &mut impl Rng) -> V
         y: final_angle.sin(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+
+    #[test]
+    fn empty_composition_samples_nothing() {
+        let mut rng = WyRand::seed_from_u64(0);
+        assert_eq!(BitComposition::default().sample(&mut rng), None);
+    }
+
+    #[test]
+    fn sample_only_sources_with_mass() {
+        let mut rng = WyRand::seed_from_u64(0);
+        let mut composition = BitComposition::single(BitSource::Blade, 3.0);
+        composition.add(BitSource::Ranged, 1.0);
+
+        let mut blades = 0;
+        for _ in 0..1000 {
+            match composition.sample(&mut rng) {
+                Some(BitSource::Blade) => blades += 1,
+                Some(BitSource::Ranged) => {}
+                source => panic!("sampled {source:?}"),
+            }
+        }
+        // Three quarters of the mass is blade.
+        assert!((650..850).contains(&blades), "{blades} blades");
+    }
+
+    #[test]
+    fn scale_keeps_proportions() {
+        let mut composition = BitComposition::single(BitSource::Heavy, 20.0);
+        composition.add(BitSource::Wild, 10.0);
+        composition.scale(0.5);
+
+        assert_eq!(composition.get(BitSource::Heavy), 10.0);
+        assert_eq!(composition.get(BitSource::Wild), 5.0);
+        assert_eq!(composition.total(), 15.0);
+    }
+
+    #[test]
+    fn merged_bits_keep_their_composition() {
+        let merged = BitComposition::single(BitSource::Ranged, 2.0);
+        let bit = BitComposition::of(2.0, Some(&BitSource::Blade), Some(&merged));
+        let mut composition = BitComposition::of(1.0, Some(&BitSource::Blade), None);
+        composition.merge(&bit);
+
+        assert_eq!(composition.get(BitSource::Blade), 1.0);
+        assert_eq!(composition.get(BitSource::Ranged), 2.0);
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    Layer,
+    enemy::{FinisherTarget, Staggered},
+    faction::Faction,
+    health::{EnemyHitbox, FriendlyHitbox},
+    projectile::Projectile,
+    query::AncestorQuery,
+    weapon::{AlreadyHit, ApplyWeaponDurability, Weapon, WeaponDurability},
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, parry_window)
+        .add_observer(parry)
+        .add_observer(block_cost);
+}
+
+/// Lets an entity guard against hits arriving from the direction it faces.
+#[derive(Clone, Copy, Component)]
+pub struct Block {
+    /// Fraction of damage and knockback absorbed by a block.
+    pub reduction: f32,
+    /// Full width in radians of the guarded arc, centered on the facing direction.
+    pub arc: f32,
+    /// Seconds at the start of a block in which hits are parried.
+    pub parry_window: f32,
+}
+
+impl Default for Block {
+    fn default() -> Self {
+        Self {
+            reduction: 0.75,
+            arc: PI * 0.75,
+            parry_window: 0.15,
+        }
+    }
+}
+
+impl Block {
+    /// Resolves a hit from `from` against an entity with `transform`.
+    pub fn guard(&self, blocking: &Blocking, transform: &GlobalTransform, from: Vec2) -> Guard {
+        let facing = transform.rotation() * Vec3::Y;
+        let to_attacker = (from - transform.translation().xy()).normalize_or_zero();
+        if facing.xy().angle_to(to_attacker).abs() > self.arc / 2.0 {
+            Guard::Open
+        } else if !blocking.parry.is_finished() {
+            Guard::Parried
+        } else {
+            Guard::Blocked(self.reduction)
+        }
+    }
+}
+
+/// The entity is actively guarding with its [`Block`].
+#[derive(Component)]
+pub struct Blocking {
+    parry: Timer,
+}
+
+impl Blocking {
+    pub fn new(block: &Block) -> Self {
+        Self {
+            parry: Timer::from_seconds(block.parry_window, TimerMode::Once),
+        }
+    }
+}
+
+/// How a hit against a [`Blocking`] entity resolves.
+pub enum Guard {
+    Open,
+    /// Damage and knockback are reduced by the fraction.
+    Blocked(f32),
+    Parried,
+}
+
+/// A hit was reduced by a [`Block`].
+#[derive(EntityEvent)]
+pub struct Blocked(pub Entity);
+
+/// A hit was parried and never landed.
+#[derive(EntityEvent)]
+pub struct Parried {
+    #[event_target]
+    pub defender: Entity,
+    /// The hitbox that was parried.
+    pub attacker: Entity,
+}
+
+fn parry_window(time: Res<Time>, mut blocking: Query<&mut Blocking>) {
+    for mut blocking in blocking.iter_mut() {
+        blocking.parry.tick(time.delta());
+    }
+}
+
+/// Staggers melee attackers and reflects projectiles.
+fn parry(
+    parried: On<Parried>,
+    mut commands: Commands,
+    mut projectiles: Query<(&mut LinearVelocity, Has<EnemyHitbox>), With<Projectile>>,
+    attackers: AncestorQuery<Entity, With<RigidBody>>,
+    factions: Query<&Faction>,
+) {
+    if let Ok((mut velocity, enemy)) = projectiles.get_mut(parried.attacker) {
+        velocity.0 = -velocity.0;
+        let mut layers = if enemy {
+            FriendlyHitbox::collision_layers()
+        } else {
+            EnemyHitbox::collision_layers()
+        };
+        layers.filters |= Layer::Wall.to_bits();
+
+        let mut entity = commands.entity(parried.attacker);
+        if enemy {
+            entity.remove::<EnemyHitbox>().insert(FriendlyHitbox);
+        } else {
+            entity.remove::<FriendlyHitbox>().insert(EnemyHitbox);
+        }
+        // Reflected projectiles fight for the defender.
+        match factions.get(parried.defender) {
+            Ok(faction) => entity.insert(*faction),
+            Err(_) => entity.remove::<Faction>(),
+        };
+        // The hitbox markers require `Sensor`, but projectiles must stay solid to ricochet.
+        entity
+            .insert((layers, AlreadyHit::default()))
+            .remove::<Sensor>();
+    } else if let Ok(attacker) = attackers.get(parried.attacker) {
+        commands
+            .entity(attacker)
+            .insert((Staggered::from_seconds(1.0), FinisherTarget::flashing()));
+    }
+}
+
+/// Blocking wears down the defender's weapon.
+fn block_cost(
+    blocked: On<Blocked>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    mut weapons: Query<(Entity, &mut WeaponDurability), With<Weapon>>,
+    apply_durability: AncestorQuery<&ApplyWeaponDurability>,
+) {
+    let Ok(children) = children.get(blocked.0) else {
+        return;
+    };
+    let mut iter = weapons.iter_many_mut(children);
+    if let Some((weapon, mut durability)) = iter.fetch_next()
+        && apply_durability.get(weapon).is_ok()
+    {
+        let (WeaponDurability::Fire(durability) | WeaponDurability::Hit(durability)) =
+            &mut *durability;
+        *durability = durability.saturating_sub(1);
+        if *durability == 0 {
+            commands.entity(weapon).despawn();
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use crate::{
+    Layer,
+    bits::{self, BitProducer, BitSource, coalescence::CoalesceEvent},
+    enemy::{FinisherDamage, FinisherTarget, Staggered, SteerTarget},
+    faction::Faction,
+    feedback::popup,
+    health::{CurrentHealth, EnemyHurtbox, MaxHealth, WeakPoint},
+    player::Player,
+    projectile::{ProjectileSize, ProjectileSpeed},
+    status::{BaseColor, Stunned},
+    weapon::{
+        AttackCooldown, AttackDamage, AttackDuration, AttackHandler, Damage, InfiniteAmmo,
+        MultiHit, NoDrop, PooledAttacks, Spread, TriggerWeapon, Weapon, WeaponKnockback,
+        WeaponReach,
+    },
+};
+use avian2d::prelude::*;
+use bevy::{
+    color::palettes::css::{BLACK, DARK_RED, ORANGE_RED, RED, YELLOW},
+    prelude::*,
+};
+use std::f32::consts::TAU;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (advance_phase, run_patterns, update_boss_bars).chain(),
+    )
+    .add_observer(spawn_boss)
+    .add_observer(close_stagger_window)
+    .add_observer(restore_tint);
+}
+
+/// Seconds a boss can be finished after entering a new phase.
+const STAGGER_WINDOW: f32 = 2.5;
+
+/// A large enemy that fights in [`Phase`]s.
+///
+/// Bosses can only be finished during the stagger window that opens when a
+/// new phase begins, and finishers deal [`FinisherDamage`] instead of killing.
+#[derive(Clone, Copy, Component)]
+#[require(
+    Transform,
+    RigidBody::Dynamic,
+    CollisionLayers = Self::collision_layers(),
+    LockedAxes::ROTATION_LOCKED,
+    MaxLinearSpeed(25.0),
+    InfiniteAmmo,
+    Faction::Horde,
+    BossState,
+)]
+pub struct Boss {
+    pub name: &'static str,
+    pub color: Color,
+    /// Ordered by descending health threshold, starting at `1.0`.
+    pub phases: &'static [Phase],
+}
+
+impl Boss {
+    fn collision_layers() -> CollisionLayers {
+        CollisionLayers::new(Layer::Empty, Layer::Wall)
+    }
+}
+
+#[derive(Debug)]
+pub struct Phase {
+    /// Fraction of max health at or below which the phase begins.
+    pub threshold: f32,
+    /// Performed in order, looping back to the first.
+    pub patterns: &'static [Pattern],
+    /// Seconds between patterns.
+    pub cooldown: f32,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Pattern {
+    /// Spins the boss's [`BossArm`] in a full circle.
+    Sweep,
+    /// Fires `count` bullets evenly around the boss from its [`BossCannon`].
+    BulletRing { count: usize },
+    /// Coalesces `count` enemies in a circle of `radius` around the boss.
+    Summon { count: usize, radius: f32 },
+}
+
+pub const WARDEN: Boss = Boss {
+    name: "The Warden",
+    color: Color::Srgba(DARK_RED),
+    phases: &[
+        Phase {
+            threshold: 1.0,
+            patterns: &[Pattern::Sweep, Pattern::BulletRing { count: 8 }],
+            cooldown: 2.5,
+        },
+        Phase {
+            threshold: 0.6,
+            patterns: &[
+                Pattern::BulletRing { count: 12 },
+                Pattern::Summon {
+                    count: 2,
+                    radius: 80.0,
+                },
+                Pattern::Sweep,
+            ],
+            cooldown: 2.0,
+        },
+        Phase {
+            threshold: 0.3,
+            patterns: &[
+                Pattern::Sweep,
+                Pattern::BulletRing { count: 16 },
+                Pattern::BulletRing { count: 16 },
+                Pattern::Summon {
+                    count: 3,
+                    radius: 100.0,
+                },
+            ],
+            cooldown: 1.5,
+        },
+    ],
+};
+
+/// Spawns [`WARDEN`] with a weak point on each flank.
+///
+/// Hits are deduplicated per root, so the weak points sit clear of the body
+/// hurtbox rather than sharing a hit with it.
+pub fn warden() -> impl Bundle {
+    let size = 80.0;
+    let weak_point_radius = 8.0;
+    let weak_point_offset = size / 2.0 + weak_point_radius + 2.0;
+    (
+        WARDEN,
+        MaxHealth(60.0),
+        FinisherDamage(10.0),
+        Sprite::from_color(WARDEN.color, Vec2::splat(size)),
+        BaseColor(WARDEN.color),
+        Collider::circle(size / 2.0),
+        children![
+            (
+                EnemyHurtbox,
+                Collider::circle(size / 2.0),
+                Transform::default(),
+            ),
+            (
+                EnemyHurtbox,
+                WeakPoint(2.0),
+                Collider::circle(weak_point_radius),
+                Sprite::from_color(YELLOW, Vec2::splat(weak_point_radius * 2.0)),
+                Transform::from_xyz(-weak_point_offset, 0.0, 1.0),
+            ),
+            (
+                EnemyHurtbox,
+                WeakPoint(2.0),
+                Collider::circle(weak_point_radius),
+                Sprite::from_color(YELLOW, Vec2::splat(weak_point_radius * 2.0)),
+                Transform::from_xyz(weak_point_offset, 0.0, 1.0),
+            ),
+            (BossArm, WeaponReach(size * 0.8)),
+            (BossCannon, WeaponReach(size * 0.6)),
+        ],
+    )
+}
+
+/// The melee weapon swung by [`Pattern::Sweep`].
+///
+/// Keeps hitting anything caught against it for the length of the sweep.
+#[derive(Component)]
+#[require(
+    Weapon,
+    NoDrop,
+    Damage(2.0),
+    BitProducer(3),
+    BitSource::Heavy,
+    WeaponKnockback(400.0),
+    AttackHandler::spin(),
+    AttackDamage(Damage(2.0)),
+    AttackDuration::from_seconds(0.5),
+    AttackCooldown::from_seconds(0.5),
+    MultiHit = MultiHit::from_seconds(0.25),
+    Collider::rectangle(20.0, 70.0),
+    Sprite::from_color(BLACK, Vec2::new(20.0, 70.0)),
+    Name::new("Boss arm")
+)]
+pub struct BossArm;
+
+/// The ranged weapon fired by [`Pattern::BulletRing`].
+#[derive(Component)]
+#[require(
+    Weapon,
+    NoDrop,
+    Damage(1.0),
+    BitProducer(1),
+    BitSource::Ranged,
+    AttackHandler::bullet(),
+    AttackDamage(Damage(1.0)),
+    AttackCooldown::from_seconds(0.2),
+    PooledAttacks,
+    ProjectileSpeed(180.0),
+    ProjectileSize(12.0),
+    Collider::rectangle(12.0, 12.0),
+    Sprite::from_color(ORANGE_RED, Vec2::splat(12.0)),
+    Name::new("Boss cannon")
+)]
+pub struct BossCannon;
+
+#[derive(Component)]
+pub struct BossState {
+    /// Index of the current [`Phase`].
+    pub phase: usize,
+    /// Index of the next [`Pattern`] in the current phase.
+    next: usize,
+    cooldown: Timer,
+}
+
+impl Default for BossState {
+    fn default() -> Self {
+        Self {
+            phase: 0,
+            next: 0,
+            cooldown: Timer::from_seconds(2.0, TimerMode::Once),
+        }
+    }
+}
+
+fn spawn_boss(
+    trigger: On<Add, Boss>,
+    mut commands: Commands,
+    bosses: Query<&Boss>,
+    player: Query<Entity, With<Player>>,
+) -> Result {
+    let boss = bosses.get(trigger.entity)?;
+    let mut entity = commands.entity(trigger.entity);
+    entity
+        .insert(Name::new(boss.name))
+        .observe(bits::produce_bits);
+    if let Ok(player) = player.single() {
+        entity.insert(SteerTarget(player));
+    }
+
+    commands.spawn((
+        Name::new("Boss bar"),
+        BossBarOf(trigger.entity),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Percent(20.0),
+            width: Val::Percent(60.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            ..Default::default()
+        },
+        children![
+            (Text::new(boss.name), TextFont::from_font_size(18.0)),
+            (
+                Node {
+                    width: Val::Percent(100.0),
+                    height: Val::Px(12.0),
+                    ..Default::default()
+                },
+                BackgroundColor(BLACK.into()),
+                children![(
+                    BossBarFill,
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..Default::default()
+                    },
+                    BackgroundColor(RED.into()),
+                )],
+            ),
+        ],
+    ));
+    Ok(())
+}
+
+/// Enters the deepest phase whose threshold the boss's health has fallen to,
+/// opening a stagger window in which it can be finished.
+fn advance_phase(
+    mut commands: Commands,
+    mut bosses: Query<
+        (
+            Entity,
+            &Boss,
+            &mut BossState,
+            &CurrentHealth,
+            &MaxHealth,
+            &GlobalTransform,
+        ),
+        Changed<CurrentHealth>,
+    >,
+) {
+    for (entity, boss, mut state, current, max, transform) in bosses.iter_mut() {
+        let fraction = current.0 / max.0;
+        let phase = boss
+            .phases
+            .iter()
+            .rposition(|phase| fraction <= phase.threshold)
+            .unwrap_or_default();
+        if phase <= state.phase || current.0 <= 0.0 {
+            continue;
+        }
+
+        state.phase = phase;
+        state.next = 0;
+        state.cooldown = Timer::from_seconds(boss.phases[phase].cooldown, TimerMode::Once);
+        commands.entity(entity).insert((
+            Staggered::from_seconds(STAGGER_WINDOW),
+            FinisherTarget::flashing(),
+        ));
+        commands.spawn(popup(
+            format!("{} enrages!", boss.name),
+            18.0,
+            RED.into(),
+            transform.translation().xy(),
+        ));
+    }
+}
+
+fn run_patterns(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut bosses: Query<
+        (&Boss, &mut BossState, &GlobalTransform, &Children),
+        (Without<Staggered>, Without<Stunned>),
+    >,
+    arms: Query<Entity, With<BossArm>>,
+    cannons: Query<Entity, With<BossCannon>>,
+) {
+    for (boss, mut state, transform, children) in bosses.iter_mut() {
+        if !state.cooldown.tick(time.delta()).is_finished() {
+            continue;
+        }
+        let Some(phase) = boss.phases.get(state.phase) else {
+            continue;
+        };
+        state.cooldown = Timer::from_seconds(phase.cooldown, TimerMode::Once);
+        if phase.patterns.is_empty() {
+            continue;
+        }
+        let pattern = phase.patterns[state.next % phase.patterns.len()];
+        state.next += 1;
+
+        match pattern {
+            Pattern::Sweep => {
+                for arm in arms.iter_many(children) {
+                    commands.entity(arm).trigger(TriggerWeapon::enemy);
+                }
+            }
+            Pattern::BulletRing { count } => {
+                let spread = Spread {
+                    count,
+                    angle: TAU - TAU / count.max(1) as f32,
+                };
+                for cannon in cannons.iter_many(children) {
+                    commands
+                        .entity(cannon)
+                        .insert(spread)
+                        .trigger(TriggerWeapon::enemy);
+                }
+            }
+            Pattern::Summon { count, radius } => {
+                let translation = transform.translation();
+                for i in 0..count {
+                    let angle = TAU * i as f32 / count as f32;
+                    let offset = Vec2::from_angle(angle) * radius;
+                    commands
+                        .spawn(GlobalTransform::from_translation(
+                            translation + offset.extend(0.0),
+                        ))
+                        .trigger(CoalesceEvent::new)
+                        .despawn();
+                }
+            }
+        }
+    }
+}
+
+/// Bosses are only finishable while staggered.
+fn close_stagger_window(
+    trigger: On<Remove, Staggered>,
+    mut commands: Commands,
+    bosses: Query<(), With<Boss>>,
+) {
+    if bosses.contains(trigger.entity) {
+        commands
+            .entity(trigger.entity)
+            .try_remove::<FinisherTarget>();
+    }
+}
+
+/// Undoes the flashing of a finisher target that survived.
+fn restore_tint(trigger: On<Remove, FinisherTarget>, mut bosses: Query<(&Boss, &mut Sprite)>) {
+    if let Ok((boss, mut sprite)) = bosses.get_mut(trigger.entity) {
+        sprite.color = boss.color;
+    }
+}
+
+#[derive(Component)]
+#[relationship_target(relationship = BossBarOf, linked_spawn)]
+pub struct BossBars(Vec<Entity>);
+
+/// A health bar across the top of the screen.
+#[derive(Component)]
+#[relationship(relationship_target = BossBars)]
+pub struct BossBarOf(Entity);
+
+#[derive(Component)]
+struct BossBarFill;
+
+fn update_boss_bars(
+    bosses: Query<(&CurrentHealth, &MaxHealth, &BossBars)>,
+    children: Query<&Children>,
+    mut fills: Query<&mut Node, With<BossBarFill>>,
+) {
+    for (current, max, bars) in bosses.iter() {
+        let fraction = (current.0 / max.0).clamp(0.0, 1.0);
+        for bar in bars.iter() {
+            let mut iter = fills.iter_many_mut(children.iter_descendants(bar));
+            while let Some(mut node) = iter.fetch_next() {
+                node.width = Val::Percent(fraction * 100.0);
+            }
+        }
+    }
+}
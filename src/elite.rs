@@ -0,0 +1,361 @@
+use crate::{
+    bits::{BitEvent, BitProducer, BitSource, BitYield},
+    block::{Block, Blocking},
+    faction::Faction,
+    health::{Armor, CurrentHealth, DeathEvent, DeathSystems, EnemyHitbox, MaxHealth},
+    weapon::{AttackDuration, Damage, HitEvent, WeaponKnockback},
+};
+use avian2d::prelude::*;
+use bevy::{
+    color::palettes::css::{CRIMSON, DEEP_SKY_BLUE, GOLD, ORANGE_RED, SILVER},
+    prelude::*,
+};
+use rand::Rng;
+use std::f32::consts::{PI, TAU};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedPostUpdate,
+        (split_on_death, explode_on_death).in_set(DeathSystems::Prepare),
+    )
+    .add_observer(spawn_aura)
+    .add_observer(hasten)
+    .add_observer(raise_shield)
+    .add_observer(drain_life);
+}
+
+/// Every modifier an elite can roll, along with its relative chance to be rolled.
+pub const MODIFIERS: &[(EliteModifier, u32)] = &[
+    (EliteModifier::Armored, 4),
+    (EliteModifier::Fast, 4),
+    (EliteModifier::Splitting, 3),
+    (EliteModifier::Shielded, 3),
+    (EliteModifier::Vampiric, 2),
+    (EliteModifier::Explosive, 2),
+];
+
+/// Chance for an enemy to spawn as an elite at depth zero.
+const BASE_ELITE_CHANCE: f32 = 0.05;
+
+/// Elite chance gained with every level of [`Depth`](crate::run::Depth).
+const ELITE_CHANCE_PER_DEPTH: f32 = 0.03;
+
+const MAX_ELITE_CHANCE: f32 = 0.5;
+
+/// Bits produced by hits on an elite are multiplied by this.
+const ELITE_BIT_YIELD: f32 = 2.0;
+
+/// Bits released in every direction by a [`Splitting`] elite's death.
+const SPLIT_BITS: usize = 6;
+
+const EXPLOSION_RADIUS: f32 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EliteModifier {
+    Armored,
+    Fast,
+    Splitting,
+    Shielded,
+    Vampiric,
+    Explosive,
+}
+
+impl EliteModifier {
+    fn insert(self, entity: &mut EntityCommands) {
+        match self {
+            Self::Armored => entity.insert(Armored),
+            Self::Fast => entity.insert(Fast),
+            Self::Splitting => entity.insert(Splitting),
+            Self::Shielded => entity.insert(Shielded),
+            Self::Vampiric => entity.insert(Vampiric),
+            Self::Explosive => entity.insert(Explosive),
+        };
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Armored => SILVER.into(),
+            Self::Fast => DEEP_SKY_BLUE.into(),
+            Self::Splitting => GOLD.into(),
+            Self::Shielded => Color::WHITE,
+            Self::Vampiric => CRIMSON.into(),
+            Self::Explosive => ORANGE_RED.into(),
+        }
+    }
+}
+
+/// An enemy empowered by one or more [`EliteModifier`]s.
+///
+/// Each modifier is inserted as its own component, so they can also be added by hand.
+#[derive(Component)]
+#[require(BitYield(ELITE_BIT_YIELD))]
+pub struct Elite(pub Vec<EliteModifier>);
+
+/// Rolls whether a freshly spawned enemy is an elite, and which modifiers it gets.
+///
+/// The chance grows with `depth`, and every extra modifier is rolled at half the chance.
+pub fn roll(entity: &mut EntityCommands, rng: &mut impl Rng, depth: usize) {
+    let chance =
+        (BASE_ELITE_CHANCE + ELITE_CHANCE_PER_DEPTH * depth as f32).min(MAX_ELITE_CHANCE) as f64;
+    if !rng.random_bool(chance) {
+        return;
+    }
+
+    let mut candidates = MODIFIERS.to_vec();
+    let mut modifiers = Vec::new();
+    while !candidates.is_empty() && (modifiers.is_empty() || rng.random_bool(chance / 2.0)) {
+        let total: u32 = candidates.iter().map(|(_, weight)| weight).sum();
+        let mut selection = rng.random_range(0..total);
+        let index = candidates
+            .iter()
+            .position(|(_, weight)| {
+                let found = selection < *weight;
+                selection = selection.saturating_sub(*weight);
+                found
+            })
+            .unwrap();
+        let (modifier, _) = candidates.swap_remove(index);
+        modifier.insert(entity);
+        modifiers.push(modifier);
+    }
+    entity.insert(Elite(modifiers));
+}
+
+/// Takes less damage from every hit.
+#[derive(Component)]
+#[require(Armor(0.4))]
+pub struct Armored;
+
+/// Moves faster than others of its archetype.
+#[derive(Component)]
+pub struct Fast;
+
+/// Releases a burst of bits in every direction on death.
+#[derive(Component)]
+pub struct Splitting;
+
+/// Permanently guards against hits from the front.
+#[derive(Component)]
+pub struct Shielded;
+
+/// Heals by a fraction of the damage its melee attacks deal.
+#[derive(Component)]
+pub struct Vampiric;
+
+/// Explodes on death, damaging anything hostile to its [`Faction`] nearby.
+#[derive(Component)]
+pub struct Explosive;
+
+impl Fast {
+    const SPEED_MULTIPLIER: f32 = 1.75;
+}
+
+impl Shielded {
+    const BLOCK: Block = Block {
+        reduction: 0.6,
+        arc: PI * 0.75,
+        parry_window: 0.0,
+    };
+}
+
+impl Vampiric {
+    const LIFESTEAL: f32 = 0.5;
+}
+
+/// A glow behind an [`Elite`], tinted by its first modifier.
+#[derive(Component)]
+struct EliteAura;
+
+fn spawn_aura(trigger: On<Add, Elite>, mut commands: Commands, elites: Query<(&Elite, &Sprite)>) {
+    let Ok((elite, sprite)) = elites.get(trigger.entity) else {
+        return;
+    };
+    let color = elite
+        .0
+        .first()
+        .map_or(Color::WHITE, |modifier| modifier.color());
+    let size = sprite.custom_size.unwrap_or(Vec2::splat(20.0)) * 1.6;
+    commands.spawn((
+        Name::new("Elite aura"),
+        EliteAura,
+        ChildOf(trigger.entity),
+        Sprite::from_color(color.with_alpha(0.4), size),
+        Transform::from_xyz(0.0, 0.0, -1.0),
+    ));
+}
+
+fn hasten(trigger: On<Add, Fast>, mut speeds: Query<&mut MaxLinearSpeed>) {
+    if let Ok(mut speed) = speeds.get_mut(trigger.entity) {
+        speed.0 *= Fast::SPEED_MULTIPLIER;
+    }
+}
+
+fn raise_shield(trigger: On<Add, Shielded>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity)
+        .insert((Shielded::BLOCK, Blocking::new(&Shielded::BLOCK)));
+}
+
+fn drain_life(
+    hit: On<HitEvent>,
+    health: Query<(), With<CurrentHealth>>,
+    mut vampires: Query<(&mut CurrentHealth, &MaxHealth), With<Vampiric>>,
+) {
+    let Some(wielder) = hit.wielder else {
+        return;
+    };
+    if !health.contains(hit.target) {
+        return;
+    }
+    if let Ok((mut current, max)) = vampires.get_mut(wielder) {
+        current.0 = (current.0 + hit.damage * Vampiric::LIFESTEAL).min(max.0);
+    }
+}
+
+fn split_on_death(
+    mut reader: MessageReader<DeathEvent>,
+    mut writer: MessageWriter<BitEvent>,
+    splitting: Query<&GlobalTransform, With<Splitting>>,
+) {
+    for event in reader.read() {
+        let Ok(transform) = splitting.get(event.0) else {
+            continue;
+        };
+        for i in 0..4 {
+            writer.write(BitEvent {
+                direction: Vec2::from_angle(TAU * i as f32 / 4.0),
+                translation: transform.translation().xy(),
+                bits: SPLIT_BITS,
+                source: BitSource::Wild,
+            });
+        }
+    }
+}
+
+fn explode_on_death(
+    mut commands: Commands,
+    mut reader: MessageReader<DeathEvent>,
+    explosive: Query<(&GlobalTransform, Option<&Faction>), With<Explosive>>,
+) {
+    for event in reader.read() {
+        let Ok((transform, faction)) = explosive.get(event.0) else {
+            continue;
+        };
+        let mut explosion = commands.spawn((
+            Name::new("Explosion"),
+            EnemyHitbox,
+            Damage(2.0),
+            WeaponKnockback(500.0),
+            BitProducer(0),
+            AttackDuration::from_seconds(0.15),
+            Collider::circle(EXPLOSION_RADIUS),
+            Sprite::from_color(
+                ORANGE_RED.with_alpha(0.5),
+                Vec2::splat(EXPLOSION_RADIUS * 2.0),
+            ),
+            Transform::from_translation(transform.translation()),
+        ));
+        if let Some(faction) = faction {
+            explosion.insert(*faction);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::EntityPools, weapon};
+    use bevy_rand::prelude::WyRand;
+    use rand::SeedableRng;
+
+    /// Rolls `count` enemies at `depth`, returning the modifiers of each elite.
+    fn roll_enemies(seed: u64, depth: usize, count: usize) -> (World, Vec<(Entity, Elite)>) {
+        let mut world = World::new();
+        let mut rng = WyRand::seed_from_u64(seed);
+        let entities = (0..count)
+            .map(|_| {
+                let mut commands = world.commands();
+                let mut entity = commands.spawn_empty();
+                roll(&mut entity, &mut rng, depth);
+                entity.id()
+            })
+            .collect::<Vec<_>>();
+        world.flush();
+
+        let elites = entities
+            .into_iter()
+            .filter_map(|entity| {
+                let elite = world.entity_mut(entity).take::<Elite>()?;
+                Some((entity, elite))
+            })
+            .collect();
+        (world, elites)
+    }
+
+    #[test]
+    fn roll_is_seeded() {
+        let modifiers = |seed| {
+            let (_, elites) = roll_enemies(seed, 5, 200);
+            elites
+                .into_iter()
+                .map(|(_, elite)| elite.0)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(modifiers(3), modifiers(3));
+    }
+
+    #[test]
+    fn elites_get_distinct_modifiers() {
+        let (world, elites) = roll_enemies(0, 20, 1000);
+        assert!(!elites.is_empty());
+        for (entity, elite) in elites {
+            assert!(!elite.0.is_empty());
+            for (i, modifier) in elite.0.iter().enumerate() {
+                assert!(!elite.0[i + 1..].contains(modifier));
+            }
+            if elite.0.contains(&EliteModifier::Armored) {
+                assert!(world.entity(entity).contains::<Armored>());
+            }
+        }
+    }
+
+    #[test]
+    fn elites_grow_common_with_depth() {
+        let (_, shallow) = roll_enemies(0, 0, 1000);
+        let (_, deep) = roll_enemies(0, 20, 1000);
+        assert!(shallow.len() < deep.len());
+    }
+
+    #[test]
+    fn vampires_drain_life_from_hits_on_hurtboxes() {
+        let mut world = World::new();
+        world.init_resource::<EntityPools>();
+        world.add_observer(weapon::handle_attack);
+        world.add_observer(drain_life);
+
+        let vampire = world
+            .spawn((Vampiric, MaxHealth(10.0), CurrentHealth(5.0)))
+            .id();
+        let attack = world.spawn_empty().id();
+        let victim = world.spawn(CurrentHealth(10.0)).id();
+        let hurtbox = world.spawn(ChildOf(victim)).id();
+
+        // The attacker is taken at the hurtbox, before the hit reaches the root.
+        world.trigger(HitEvent {
+            target: hurtbox,
+            attacker: Some(attack),
+            wielder: Some(vampire),
+            damage: 4.0,
+            knockback: Vec2::ZERO,
+            bits: 0,
+            source: BitSource::Wild,
+            target_translation: Vec2::ZERO,
+            attacker_translation: Vec2::ZERO,
+            crit: false,
+        });
+        world.flush();
+
+        let healed = 5.0 + 4.0 * Vampiric::LIFESTEAL;
+        assert_eq!(world.get::<CurrentHealth>(vampire).unwrap().0, healed);
+    }
+}
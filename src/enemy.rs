@@ -1,16 +1,27 @@
 use crate::{
     Layer,
-    bits::{self, coalescence::CoalesceEvent},
+    aggro::{Aggro, Awareness, Patrol},
+    bits::{self, BitSource, coalescence::CoalesceEvent},
+    boss::Boss,
+    elite,
+    faction::Faction,
     health::{CurrentHealth, DeathEvent, DeathSystems, EnemyHurtbox, MaxHealth},
+    navigation::{FlowFields, NavGrid, NavigationSystems, SearchFields},
     physics::{Acceleration, CustomPhysicsSystems},
     player::Player,
-    weapon::{self, Broadsword, Dagger, Pistol, TriggerWeapon, Weapon, WeaponPickup, WeaponReach},
+    run::Depth,
+    scavenge::Scavenger,
+    status::{BaseColor, Stunned},
+    weapon::{
+        self, Broadsword, Dagger, InfiniteAmmo, Magazine, NoDrop, Pistol, TriggerWeapon, Weapon,
+        WeaponDurability, WeaponPickup, WeaponReach, affix,
+    },
 };
 use avian2d::prelude::{
     Collider, ColliderOf, CollisionLayers, LockedAxes, MaxLinearSpeed, RigidBody,
 };
 use bevy::{
-    color::palettes::css::{BLUE, GREEN, RED},
+    color::palettes::css::{BLUE, GRAY, GREEN, RED},
     ecs::{lifecycle::HookContext, world::DeferredWorld},
     prelude::*,
 };
@@ -21,13 +32,14 @@ pub struct EnemyPlugin;
 
 impl Plugin for EnemyPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (orient_to_player, attack))
+        app.add_systems(Update, (orient_to_target, attack, recover_from_stagger))
             .add_systems(
                 FixedPostUpdate,
                 (
                     drop_weapon_on_death.in_set(DeathSystems::Prepare),
                     ((target_vector, seperation_vector), apply_force_vectors)
                         .chain()
+                        .after(NavigationSystems::Plan)
                         .before(CustomPhysicsSystems::Acceleration),
                 ),
             )
@@ -53,6 +65,7 @@ macro_rules! enemy {
         $(#[$attrs])*
         #[require(
             Sprite::from_color(Self::color(), Vec2::splat(Self::VISUAL_RADIUS * 2.0)),
+            BaseColor(Self::color()),
         )]
         #[component(on_insert = Self::insert)]
         pub struct $ident;
@@ -88,6 +101,9 @@ macro_rules! enemy {
     CollisionLayers = Self::collision_layers(),
     LockedAxes::ROTATION_LOCKED,
     MaxLinearSpeed(40.0),
+    InfiniteAmmo,
+    Aggro,
+    Faction::Horde,
 )]
 pub struct Enemy;
 
@@ -118,9 +134,10 @@ fn spawn_enemy(
     mut commands: Commands,
     transforms: Query<&GlobalTransform>,
     player: Query<Entity, With<Player>>,
+    depth: Res<Depth>,
     mut rng: Single<&mut WyRand, With<GlobalRng>>,
 ) -> Result {
-    let transform = transforms.get(gibblet.0)?;
+    let transform = transforms.get(gibblet.entity)?;
     let mut entity = commands.spawn((
         Enemy,
         Collider::circle(10.0),
@@ -132,17 +149,28 @@ fn spawn_enemy(
     entity
         .observe(weapon::weapon_knockback)
         .observe(bits::produce_bits);
-    sample_enemy_type(entity, &mut rng);
+    let id = entity.id();
+    let source = gibblet.composition.sample(&mut rng);
+    sample_enemy_type(entity, &mut rng, source);
+    elite::roll(&mut commands.entity(id), &mut rng, depth.0);
     Ok(())
 }
 
-fn sample_enemy_type(mut entity: EntityCommands, rng: &mut impl Rng) {
-    let enemy_type = rng.random_range(0..=2);
+/// Picks the archetype that matches the [`BitSource`] of the coalesced bits,
+/// or a random one for [`BitSource::Wild`] bits.
+fn sample_enemy_type(mut entity: EntityCommands, rng: &mut impl Rng, source: Option<BitSource>) {
+    let enemy_type = match source {
+        Some(BitSource::Blade) => 0,
+        Some(BitSource::Ranged) => 1,
+        Some(BitSource::Heavy) => 2,
+        Some(BitSource::Wild) | None => rng.random_range(0..=3),
+    };
     match enemy_type {
         0 => {
             let size = 20.0;
             entity.insert((
                 Sprite::from_color(RED, Vec2::splat(size)),
+                BaseColor(RED.into()),
                 MaxHealth(3.0),
                 EnableAttacks,
                 children![
@@ -159,6 +187,7 @@ fn sample_enemy_type(mut entity: EntityCommands, rng: &mut impl Rng) {
             let size = 25.0;
             entity.insert((
                 Sprite::from_color(GREEN, Vec2::splat(size)),
+                BaseColor(GREEN.into()),
                 MaxHealth(2.0),
                 EnableAttacks,
                 children![
@@ -175,6 +204,7 @@ fn sample_enemy_type(mut entity: EntityCommands, rng: &mut impl Rng) {
             let size = 30.0;
             entity.insert((
                 Sprite::from_color(BLUE, Vec2::splat(size)),
+                BaseColor(BLUE.into()),
                 MaxHealth(4.0),
                 EnableAttacks,
                 children![
@@ -187,38 +217,69 @@ fn sample_enemy_type(mut entity: EntityCommands, rng: &mut impl Rng) {
                 ],
             ));
         }
+        3 => {
+            let size = Scavenger::SIZE;
+            entity.insert((
+                Sprite::from_color(GRAY, Vec2::splat(size)),
+                BaseColor(GRAY.into()),
+                MaxHealth(2.0),
+                Scavenger,
+                Faction::Scavengers,
+                children![(
+                    EnemyHurtbox,
+                    avian2d::prelude::Collider::rectangle(size, size),
+                    Transform::default(),
+                )],
+            ));
+        }
         _ => unreachable!(),
     }
 }
 
+/// Enemies only attack targets they are engaged with, so ranged enemies
+/// never fire without a clear shot.
 fn attack(
     mut commands: Commands,
-    enemies: Query<Entity, (With<Enemy>, With<EnableAttacks>)>,
+    enemies: Query<
+        (Entity, &Awareness),
+        (
+            With<Enemy>,
+            With<EnableAttacks>,
+            Without<Staggered>,
+            Without<Stunned>,
+        ),
+    >,
     mut rng: Single<&mut WyRand, With<GlobalRng>>,
 ) {
-    for entity in enemies.iter() {
-        if rng.random_bool(0.02) {
+    for (entity, awareness) in enemies.iter() {
+        if awareness.is_engaged() && rng.random_bool(0.02) {
             commands.entity(entity).trigger(TriggerWeapon::enemy);
         }
     }
 }
 
+/// Drops the enemy's weapon with a freshly rolled [`Rarity`](weapon::affix::Rarity).
 fn drop_weapon_on_death(
     mut commands: Commands,
     mut reader: MessageReader<DeathEvent>,
     children: Query<&Children>,
-    weapons: Query<Entity, With<Weapon>>,
+    weapons: Query<(Entity, Has<Magazine>, Has<WeaponDurability>), (With<Weapon>, Without<NoDrop>)>,
     transforms: Query<&GlobalTransform>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
 ) -> Result {
     for event in reader.read() {
         let transform = transforms.get(event.0)?;
         if let Ok(children) = children.get(event.0) {
             let mut iter = weapons.iter_many(children);
-            if let Some(weapon_entity) = iter.fetch_next() {
+            if let Some((weapon_entity, ranged, durable)) = iter.fetch_next() {
                 commands
                     .entity(weapon_entity)
                     .remove::<(ChildOf, ColliderOf)>()
-                    .insert((WeaponPickup::default(), transform.compute_transform()));
+                    .insert((
+                        WeaponPickup::default(),
+                        transform.compute_transform(),
+                        affix::roll(&mut rng, ranged, durable),
+                    ));
             }
         }
     }
@@ -229,23 +290,62 @@ fn drop_weapon_on_death(
 #[require(Acceleration, TargetVector, SeperationVector)]
 pub struct SteerTarget(pub Entity);
 
+/// Backs away from the [`SteerTarget`] while closer than this distance.
+#[derive(Component)]
+pub struct KeepDistance(pub f32);
+
+/// Direction toward the [`SteerTarget`], following its flow field around walls.
 #[derive(Default, Component)]
 pub struct TargetVector(pub Vec2);
 
 #[derive(Default, Component)]
 pub struct SeperationVector(pub Vec2);
 
+/// Intent of an idle entity walking its [`Patrol`] compared to a chase.
+const PATROL_INTENT: f32 = 0.5;
+
 fn target_vector(
-    mut steering: Query<(&mut TargetVector, &GlobalTransform, &SteerTarget)>,
+    mut steering: Query<(
+        Entity,
+        &mut TargetVector,
+        &GlobalTransform,
+        &SteerTarget,
+        Option<(&Awareness, &Patrol)>,
+        Option<&KeepDistance>,
+    )>,
     targets: Query<&GlobalTransform>,
+    grid: Res<NavGrid>,
+    fields: Res<FlowFields>,
+    search_fields: Res<SearchFields>,
 ) -> Result {
-    for (mut target_vector, gt, steer_target) in steering.iter_mut() {
-        if let Ok(target) = targets.get(steer_target.0) {
-            let diff = target.translation().xy() - gt.translation().xy();
-            let new_vector = diff.normalize_or_zero();
-            if new_vector != Vec2::ZERO {
-                target_vector.0 = new_vector;
+    for (entity, mut target_vector, gt, steer_target, awareness, keep_distance) in
+        steering.iter_mut()
+    {
+        let translation = gt.translation().xy();
+        let new_vector = match awareness {
+            Some((Awareness::Idle, patrol)) => {
+                target_vector.0 = patrol.direction(translation) * PATROL_INTENT;
+                continue;
             }
+            Some((Awareness::Searching { last_seen, .. }, _)) => search_fields
+                .direction(&grid, entity, translation)
+                .unwrap_or_else(|| (*last_seen - translation).normalize_or_zero()),
+            Some((Awareness::Engaged, _)) | None => {
+                let Ok(target) = targets.get(steer_target.0) else {
+                    continue;
+                };
+                let diff = target.translation().xy() - translation;
+                if keep_distance.is_some_and(|keep| diff.length_squared() < keep.0 * keep.0) {
+                    -diff.normalize_or_zero()
+                } else {
+                    fields
+                        .direction(&grid, steer_target.0, translation)
+                        .unwrap_or_else(|| diff.normalize_or_zero())
+                }
+            }
+        };
+        if new_vector != Vec2::ZERO {
+            target_vector.0 = new_vector;
         }
     }
     Ok(())
@@ -276,7 +376,12 @@ fn seperation_vector(
     Ok(())
 }
 
-fn apply_force_vectors(mut steering: Query<(&mut Acceleration, &TargetVector, &SeperationVector)>) {
+fn apply_force_vectors(
+    mut steering: Query<
+        (&mut Acceleration, &TargetVector, &SeperationVector),
+        (Without<Staggered>, Without<Stunned>),
+    >,
+) {
     let impulse = 2.0;
     for (mut acceleration, target, seperation) in steering.iter_mut() {
         let force = target.0 * 2.0 + seperation.0 * 1.2;
@@ -284,13 +389,17 @@ fn apply_force_vectors(mut steering: Query<(&mut Acceleration, &TargetVector, &S
     }
 }
 
-fn orient_to_player(
-    mut enemies: Query<&mut Transform, With<Enemy>>,
+/// Faces the [`SteerTarget`], or the player without one.
+fn orient_to_target(
+    mut enemies: Query<(&mut Transform, Option<&SteerTarget>), Or<(With<Enemy>, With<Boss>)>>,
     player: Single<&GlobalTransform, With<Player>>,
+    targets: Query<&GlobalTransform>,
 ) {
-    let player_translation = player.translation().xy();
-    for mut transform in enemies.iter_mut() {
-        let looking_at = player_translation - transform.translation.xy();
+    for (mut transform, steer_target) in enemies.iter_mut() {
+        let target = steer_target
+            .and_then(|steer_target| targets.get(steer_target.0).ok())
+            .unwrap_or(*player);
+        let looking_at = target.translation().xy() - transform.translation.xy();
         let angle = Vec2::Y.angle_to(looking_at.normalize_or(Vec2::Y));
         transform.rotation = Quat::from_rotation_z(angle);
     }
@@ -299,16 +408,50 @@ fn orient_to_player(
 #[derive(Component)]
 pub struct FinisherTarget;
 
+/// Damage dealt by a finisher, which otherwise kills its target outright.
+#[derive(Component)]
+pub struct FinisherDamage(pub f32);
+
+impl FinisherTarget {
+    /// A finisher target that flashes to show it can be finished.
+    pub fn flashing() -> impl Bundle {
+        (
+            FinisherTarget,
+            FlashTimer(Timer::from_seconds(0.25, TimerMode::Repeating)),
+        )
+    }
+}
+
+/// The enemy can not attack or steer until the timer finishes.
+#[derive(Component)]
+pub struct Staggered(Timer);
+
+impl Staggered {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+fn recover_from_stagger(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut staggered: Query<(Entity, &mut Staggered)>,
+) {
+    for (entity, mut staggered) in staggered.iter_mut() {
+        staggered.0.tick(time.delta());
+        if staggered.0.is_finished() {
+            commands.entity(entity).remove::<Staggered>();
+        }
+    }
+}
+
 fn insert_finisher_target(
     mut commands: Commands,
     enemies: Query<(Entity, &CurrentHealth, &MaxHealth), (Changed<CurrentHealth>, With<Enemy>)>,
 ) {
     for (entity, current, max) in enemies.iter() {
         if current.0 / max.0 <= 0.25 || (current.0 <= 1.0 && max.0 > 1.0) {
-            commands.entity(entity).insert((
-                FinisherTarget,
-                FlashTimer(Timer::from_seconds(0.25, TimerMode::Repeating)),
-            ));
+            commands.entity(entity).insert(FinisherTarget::flashing());
         }
     }
 }
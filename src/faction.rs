@@ -0,0 +1,208 @@
+use crate::{
+    aggro::Aggro, enemy::SteerTarget, health::CurrentHealth, navigation::NavigationSystems,
+    scavenge::SeekingWeapon,
+};
+use bevy::prelude::*;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<FactionRelations>()
+        .add_systems(
+            FixedPostUpdate,
+            select_targets
+                .in_set(FactionSystems::Target)
+                .before(NavigationSystems::Plan),
+        )
+        .add_observer(charm)
+        .add_observer(uncharm);
+}
+
+/// Orders faction systems in the `FixedPostUpdate` schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum FactionSystems {
+    /// Every [`SteerTarget`] is pointed at a hostile root.
+    Target,
+}
+
+/// Which side a root fights for.
+///
+/// Attacks carry the faction of their wielder, and only land on roots of a
+/// hostile faction according to [`FactionRelations`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Component)]
+pub enum Faction {
+    Player,
+    Horde,
+    Scavengers,
+}
+
+impl Faction {
+    pub const COUNT: usize = 3;
+}
+
+/// Whether the row faction is hostile toward the column faction.
+const HOSTILITY: [[bool; Faction::COUNT]; Faction::COUNT] = [
+    // Player, Horde, Scavengers
+    [false, true, true],
+    [true, false, true],
+    [true, true, false],
+];
+
+/// The relationship matrix between every pair of [`Faction`]s.
+#[derive(Debug, Resource)]
+pub struct FactionRelations([[bool; Faction::COUNT]; Faction::COUNT]);
+
+impl Default for FactionRelations {
+    fn default() -> Self {
+        Self(HOSTILITY)
+    }
+}
+
+impl FactionRelations {
+    pub fn is_hostile(&self, a: Faction, b: Faction) -> bool {
+        self.0[a as usize][b as usize]
+    }
+
+    /// Sets the relationship both ways.
+    pub fn set_hostile(&mut self, a: Faction, b: Faction, hostile: bool) {
+        self.0[a as usize][b as usize] = hostile;
+        self.0[b as usize][a as usize] = hostile;
+    }
+}
+
+/// Fights for [`Faction::Player`] until removed, which restores the original faction.
+///
+/// Inserted by [`StatusKind::Charm`](crate::status::StatusKind::Charm).
+#[derive(Default, Component)]
+pub struct Charmed {
+    previous: Option<Faction>,
+}
+
+fn charm(trigger: On<Add, Charmed>, mut charmed: Query<(&mut Charmed, &mut Faction)>) {
+    if let Ok((mut charmed, mut faction)) = charmed.get_mut(trigger.entity) {
+        charmed.previous = Some(*faction);
+        *faction = Faction::Player;
+    }
+}
+
+fn uncharm(trigger: On<Remove, Charmed>, mut charmed: Query<(&Charmed, &mut Faction)>) {
+    if let Ok((charmed, mut faction)) = charmed.get_mut(trigger.entity)
+        && let Some(previous) = charmed.previous
+    {
+        *faction = previous;
+    }
+}
+
+/// Steers at the closest hostile root, sticking with the current target while
+/// it stays hostile and within the [`Aggro`] radius.
+///
+/// Scavengers [`SeekingWeapon`] keep steering at their pickup.
+fn select_targets(
+    relations: Res<FactionRelations>,
+    mut seekers: Query<
+        (
+            Entity,
+            &Faction,
+            &GlobalTransform,
+            &mut SteerTarget,
+            Option<&Aggro>,
+        ),
+        Without<SeekingWeapon>,
+    >,
+    targets: Query<(Entity, &Faction, &GlobalTransform), With<CurrentHealth>>,
+) {
+    for (entity, faction, transform, mut steer_target, aggro) in seekers.iter_mut() {
+        let translation = transform.translation().xy();
+        let Some(target) = targets
+            .iter()
+            .filter(|(target, target_faction, _)| {
+                *target != entity && relations.is_hostile(*faction, **target_faction)
+            })
+            .map(|(target, _, transform)| {
+                (
+                    target,
+                    transform.translation().xy().distance_squared(translation),
+                )
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(target, _)| target)
+        else {
+            continue;
+        };
+
+        // Keep chasing the current target if it is still hostile and in range.
+        let radius = aggro.map_or(f32::INFINITY, |aggro| aggro.radius);
+        if let Ok((_, current_faction, current)) = targets.get(steer_target.0)
+            && relations.is_hostile(*faction, *current_faction)
+            && current.translation().xy().distance_squared(translation) <= radius * radius
+        {
+            continue;
+        }
+
+        if steer_target.0 != target {
+            steer_target.0 = target;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::ecs::system::RunSystemOnce;
+
+    #[test]
+    fn factions_are_not_hostile_toward_themselves() {
+        let relations = FactionRelations::default();
+        assert!(!relations.is_hostile(Faction::Player, Faction::Player));
+        assert!(!relations.is_hostile(Faction::Horde, Faction::Horde));
+        assert!(relations.is_hostile(Faction::Player, Faction::Horde));
+        assert!(relations.is_hostile(Faction::Scavengers, Faction::Horde));
+    }
+
+    #[test]
+    fn set_hostile_is_symmetric() {
+        let mut relations = FactionRelations::default();
+        relations.set_hostile(Faction::Horde, Faction::Scavengers, false);
+        assert!(!relations.is_hostile(Faction::Horde, Faction::Scavengers));
+        assert!(!relations.is_hostile(Faction::Scavengers, Faction::Horde));
+
+        relations.set_hostile(Faction::Scavengers, Faction::Horde, true);
+        assert!(relations.is_hostile(Faction::Horde, Faction::Scavengers));
+        assert!(relations.is_hostile(Faction::Scavengers, Faction::Horde));
+    }
+
+    fn spawn_root(world: &mut World, faction: Faction, x: f32) -> Entity {
+        world
+            .spawn((
+                faction,
+                CurrentHealth(1.0),
+                GlobalTransform::from_xyz(x, 0.0, 0.0),
+            ))
+            .id()
+    }
+
+    #[test]
+    fn seekers_stick_with_their_target_until_it_leaves_aggro_radius() {
+        let mut world = World::new();
+        world.init_resource::<FactionRelations>();
+        let target = spawn_root(&mut world, Faction::Player, 80.0);
+        let closer = spawn_root(&mut world, Faction::Scavengers, 20.0);
+        let seeker = world
+            .spawn((
+                Faction::Horde,
+                GlobalTransform::default(),
+                SteerTarget(target),
+                Aggro {
+                    radius: 100.0,
+                    ..default()
+                },
+            ))
+            .id();
+
+        world.run_system_once(select_targets).unwrap();
+        assert_eq!(world.get::<SteerTarget>(seeker).unwrap().0, target);
+
+        *world.get_mut::<GlobalTransform>(target).unwrap() =
+            GlobalTransform::from_xyz(200.0, 0.0, 0.0);
+        world.run_system_once(select_targets).unwrap();
+        assert_eq!(world.get::<SteerTarget>(seeker).unwrap().0, closer);
+    }
+}
@@ -0,0 +1,129 @@
+use crate::{health::CurrentHealth, weapon::HitEvent};
+use bevy::{color::palettes::css::YELLOW, prelude::*};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<Hitstop>()
+        .add_systems(
+            Update,
+            ((end_hitstop, play_cinematics).chain(), float_popups),
+        )
+        .add_observer(start_hitstop)
+        .add_observer(spawn_damage_numbers);
+}
+
+/// Real seconds that the game freezes for after a critical hit.
+const HITSTOP_SECS: f32 = 0.08;
+
+/// Speed of virtual time during a hitstop.
+const HITSTOP_SPEED: f32 = 0.05;
+
+/// Speed of virtual time while a [`Cinematic`] plays.
+const CINEMATIC_SPEED: f32 = 0.3;
+
+/// Orthographic scale of the camera while a [`Cinematic`] plays.
+const CINEMATIC_ZOOM: f32 = 0.7;
+
+/// How quickly the camera zooms in and out of a [`Cinematic`], per real second.
+const CINEMATIC_ZOOM_RATE: f32 = 8.0;
+
+/// Briefly slows virtual time, which drives physics and animations.
+#[derive(Default, Resource)]
+struct Hitstop(Option<Timer>);
+
+fn start_hitstop(
+    hit: On<HitEvent>,
+    health: Query<(), With<CurrentHealth>>,
+    mut hitstop: ResMut<Hitstop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if hit.crit && health.contains(hit.target) {
+        hitstop.0 = Some(Timer::from_seconds(HITSTOP_SECS, TimerMode::Once));
+        time.set_relative_speed(HITSTOP_SPEED);
+    }
+}
+
+fn end_hitstop(
+    real: Res<Time<Real>>,
+    mut hitstop: ResMut<Hitstop>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if let Some(timer) = &mut hitstop.0 {
+        timer.tick(real.delta());
+        if timer.is_finished() {
+            hitstop.0 = None;
+            time.set_relative_speed(1.0);
+        }
+    }
+}
+
+/// Slows time and zooms the camera in for as long as any entity has this.
+#[derive(Component)]
+pub struct Cinematic;
+
+/// Hitstops take precedence over cinematics.
+fn play_cinematics(
+    real: Res<Time<Real>>,
+    hitstop: Res<Hitstop>,
+    cinematics: Query<(), With<Cinematic>>,
+    mut time: ResMut<Time<Virtual>>,
+    mut projection: Single<&mut Projection, With<Camera2d>>,
+) {
+    let playing = !cinematics.is_empty();
+    if hitstop.0.is_none() {
+        time.set_relative_speed(if playing { CINEMATIC_SPEED } else { 1.0 });
+    }
+
+    if let Projection::Orthographic(orthographic) = &mut **projection {
+        let zoom = if playing { CINEMATIC_ZOOM } else { 1.0 };
+        let t = (CINEMATIC_ZOOM_RATE * real.delta_secs()).min(1.0);
+        orthographic.scale += (zoom - orthographic.scale) * t;
+    }
+}
+
+/// Text that floats up and fades out, such as damage numbers.
+#[derive(Component)]
+struct Popup(Timer);
+
+/// Spawns floating `text` at `translation`.
+pub fn popup(text: impl Into<String>, size: f32, color: Color, translation: Vec2) -> impl Bundle {
+    (
+        Name::new("Popup"),
+        Popup(Timer::from_seconds(0.6, TimerMode::Once)),
+        Text2d::new(text),
+        TextFont::from_font_size(size),
+        TextColor(color),
+        Transform::from_translation(translation.extend(10.0)),
+    )
+}
+
+fn spawn_damage_numbers(
+    hit: On<HitEvent>,
+    mut commands: Commands,
+    health: Query<(), With<CurrentHealth>>,
+) {
+    if hit.damage <= 0.0 || !health.contains(hit.target) {
+        return;
+    }
+
+    let (text, size, color) = if hit.crit {
+        (format!("{:.1}!", hit.damage), 18.0, YELLOW.into())
+    } else {
+        (format!("{:.1}", hit.damage), 12.0, Color::WHITE)
+    };
+    commands.spawn(popup(text, size, color, hit.target_translation));
+}
+
+fn float_popups(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut popups: Query<(Entity, &mut Popup, &mut Transform, &mut TextColor)>,
+) {
+    for (entity, mut popup, mut transform, mut color) in popups.iter_mut() {
+        popup.0.tick(time.delta());
+        transform.translation.y += 30.0 * time.delta_secs();
+        color.0.set_alpha(popup.0.fraction_remaining());
+        if popup.0.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
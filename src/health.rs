@@ -1,4 +1,8 @@
-use crate::{Layer, query::AncestorQuery, weapon::HitEvent};
+use crate::{
+    Layer,
+    query::AncestorQuery,
+    weapon::{AlreadyHit, HitEvent},
+};
 use avian2d::prelude::*;
 use bevy::{
     color::palettes::css::{BLACK, RED},
@@ -120,13 +124,21 @@ fn update_health_bars(
 }
 
 #[derive(Default, Component)]
-#[require(Sensor)]
+#[require(Sensor, AlreadyHit)]
 pub struct Hitbox;
 
 #[derive(Default, Component)]
 #[require(Sensor)]
 pub struct Hurtbox;
 
+/// Multiplies the damage of hits taken by this hurtbox.
+#[derive(Clone, Copy, Component)]
+pub struct WeakPoint(pub f32);
+
+/// Fraction of the damage of every hit ignored by this root.
+#[derive(Clone, Copy, Component)]
+pub struct Armor(pub f32);
+
 #[derive(Component)]
 #[require(
     Hurtbox,
@@ -157,13 +169,19 @@ impl FriendlyHitbox {
     }
 }
 
+/// Also overlaps enemy hitboxes, so hostile [`Faction`](crate::faction::Faction)s can
+/// fight each other.
 #[derive(Default, Component)]
 #[require(Hurtbox, CollisionLayers = Self::collision_layers(), CollisionEventsEnabled)]
 pub struct EnemyHurtbox;
 
 impl EnemyHurtbox {
     pub fn collision_layers() -> CollisionLayers {
-        FriendlyHitbox::collision_layers()
+        let layers = [
+            Layer::FriendlyHitboxEnemyHurtbox,
+            Layer::FriendlyHurtboxEnemyHitbox,
+        ];
+        CollisionLayers::new(layers, layers)
     }
 }
 
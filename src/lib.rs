@@ -0,0 +1,47 @@
+#![allow(clippy::type_complexity)]
+#![allow(clippy::too_many_arguments)]
+
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+pub mod aggro;
+pub mod bits;
+pub mod block;
+pub mod boss;
+pub mod elite;
+pub mod enemy;
+pub mod faction;
+pub mod feedback;
+pub mod health;
+pub mod navigation;
+pub mod nest;
+pub mod physics;
+pub mod player;
+pub mod pool;
+pub mod projectile;
+pub mod query;
+pub mod run;
+pub mod scavenge;
+pub mod shop;
+pub mod stamina;
+pub mod status;
+pub mod weapon;
+
+pub const WIDTH: f32 = 1280.0;
+pub const HEIGHT: f32 = 720.0;
+
+#[derive(Default, PhysicsLayer)]
+pub enum Layer {
+    #[default]
+    Empty,
+    Wall,
+    FriendlyHurtboxEnemyHitbox,
+    FriendlyHitboxEnemyHurtbox,
+}
+
+#[cfg(not(debug_assertions))]
+pub fn name(_: impl Into<std::borrow::Cow<'static, str>>) -> () {}
+#[cfg(debug_assertions)]
+pub fn name(name: impl Into<std::borrow::Cow<'static, str>>) -> Name {
+    Name::new(name)
+}
@@ -1,13 +1,3 @@
-#![allow(clippy::type_complexity)]
-#![allow(clippy::too_many_arguments)]
-
-use crate::{
-    bits::coalescence::CoalesceEvent,
-    enemy::Dummy,
-    health::MaxHealth,
-    player::PlayerHurtbox,
-    weapon::{ApplyWeaponDurability, WeaponDurability},
-};
 use avian2d::prelude::*;
 #[cfg(feature = "debug")]
 use bevy::input::common_conditions::input_toggle_active;
@@ -15,18 +5,22 @@ use bevy::{
     log::{DEFAULT_FILTER, LogPlugin},
     prelude::*,
 };
-use player::Player;
-
-mod bits;
-mod enemy;
-mod health;
-mod physics;
-mod player;
-mod query;
-mod weapon;
-
-pub const WIDTH: f32 = 1280.0;
-pub const HEIGHT: f32 = 720.0;
+use slash::{
+    HEIGHT, Layer, WIDTH, aggro,
+    bits::{self, coalescence::CoalesceEvent},
+    block, boss, elite,
+    enemy::{self, Dummy},
+    faction, feedback,
+    health::{self, MaxHealth},
+    navigation,
+    nest::{self, Nest},
+    physics,
+    player::{self, Player, PlayerHurtbox},
+    pool, projectile, run, scavenge,
+    shop::{self, Shop},
+    stamina, status,
+    weapon::{self, AmmoPickup, ApplyWeaponDurability, WeaponDurability, WeaponPickup},
+};
 
 fn main() {
     let mut app = App::default();
@@ -68,7 +62,24 @@ fn main() {
         bits::BitsPlugin,
         health::plugin,
         weapon::plugin,
+        block::plugin,
         physics::plugin,
+        pool::plugin,
+        projectile::plugin,
+    ))
+    .add_plugins((
+        stamina::plugin,
+        status::plugin,
+        feedback::plugin,
+        run::plugin,
+        shop::plugin,
+        navigation::plugin,
+        aggro::plugin,
+        boss::plugin,
+        elite::plugin,
+        scavenge::plugin,
+        faction::plugin,
+        nest::plugin,
     ))
     .insert_resource(Gravity(Vec2::ZERO));
 
@@ -82,15 +93,6 @@ fn main() {
         .run();
 }
 
-#[derive(Default, PhysicsLayer)]
-pub enum Layer {
-    #[default]
-    Empty,
-    Wall,
-    FriendlyHurtboxEnemyHitbox,
-    FriendlyHitboxEnemyHurtbox,
-}
-
 #[cfg(debug_assertions)]
 fn close_on_escape(input: Res<ButtonInput<KeyCode>>, mut writer: MessageWriter<AppExit>) {
     if input.just_pressed(KeyCode::Escape) {
@@ -98,13 +100,6 @@ fn close_on_escape(input: Res<ButtonInput<KeyCode>>, mut writer: MessageWriter<A
     }
 }
 
-#[cfg(not(debug_assertions))]
-pub fn name(_: impl Into<std::borrow::Cow<'static, str>>) -> () {}
-#[cfg(debug_assertions)]
-pub fn name(name: impl Into<std::borrow::Cow<'static, str>>) -> Name {
-    Name::new(name)
-}
-
 fn camera(mut commands: Commands) {
     commands.spawn(Camera2d);
 }
@@ -118,7 +113,15 @@ fn spawn_training_scene(mut commands: Commands) {
         Transform::from_xyz(0.0, 80.0, 0.0),
         children![weapon::Dagger],
     ));
+    root.with_child((AmmoPickup::new(12), Transform::from_xyz(-80.0, 80.0, 0.0)));
+    root.with_child((Shop::default(), Transform::from_xyz(0.0, -120.0, 0.0)));
     level_walls(root);
+
+    commands.spawn((
+        weapon::Shotgun,
+        WeaponPickup::default(),
+        Transform::from_xyz(80.0, 80.0, 0.0),
+    ));
 }
 
 #[allow(unused)]
@@ -143,15 +146,18 @@ fn spawn_scene(mut commands: Commands) {
     root.with_child(GlobalTransform::from(Transform::from_translation(
         Vec3::new(100.0, 100.0, 0.0),
     )))
-    .trigger(CoalesceEvent);
+    .trigger(CoalesceEvent::new);
     root.with_child(GlobalTransform::from(Transform::from_translation(
         Vec3::new(-100.0, 100.0, 0.0),
     )))
-    .trigger(CoalesceEvent);
+    .trigger(CoalesceEvent::new);
     root.with_child(GlobalTransform::from(Transform::from_translation(
         Vec3::new(0.0, 0.0, 0.0),
     )))
-    .trigger(CoalesceEvent);
+    .trigger(CoalesceEvent::new);
+    root.with_child((boss::warden(), Transform::from_xyz(0.0, 200.0, 0.0)));
+    root.with_child((Nest, Transform::from_xyz(-400.0, 200.0, 0.0)));
+    root.with_child((Nest, Transform::from_xyz(400.0, 200.0, 0.0)));
 
     level_walls(root);
 }
@@ -0,0 +1,300 @@
+use crate::{Layer, aggro::Awareness, enemy::SteerTarget};
+use avian2d::prelude::*;
+use bevy::{
+    ecs::entity::{EntityHashMap, EntityHashSet},
+    prelude::*,
+};
+use std::{cmp::Reverse, collections::BinaryHeap};
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<NavGrid>()
+        .init_resource::<FlowFields>()
+        .init_resource::<SearchFields>()
+        .add_systems(
+            FixedPostUpdate,
+            (bake_nav_grid, (update_flow_fields, update_search_fields))
+                .chain()
+                .in_set(NavigationSystems::Plan),
+        );
+}
+
+/// Orders navigation systems in the `FixedPostUpdate` schedule.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub enum NavigationSystems {
+    /// The [`NavGrid`], [`FlowFields`] and [`SearchFields`] are brought up to date.
+    Plan,
+}
+
+/// Width and height of a [`NavGrid`] cell.
+pub const CELL_SIZE: f32 = 16.0;
+
+/// Distance kept between the center of a walkable cell and any wall.
+const CLEARANCE: f32 = 12.0;
+
+/// Distance a goal must move before its [`FlowField`] is re-planned.
+const REPLAN_DISTANCE: f32 = 32.0;
+
+const STRAIGHT_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+/// Walkable cells baked from every [`Layer::Wall`] collider.
+///
+/// Re-baked whenever a wall is spawned, moved or despawned.
+#[derive(Default, Resource)]
+pub struct NavGrid {
+    origin: Vec2,
+    size: IVec2,
+    blocked: Vec<bool>,
+    walls: EntityHashSet,
+}
+
+impl NavGrid {
+    pub fn cell(&self, position: Vec2) -> Option<IVec2> {
+        let cell = ((position - self.origin) / CELL_SIZE).floor().as_ivec2();
+        self.contains(cell).then_some(cell)
+    }
+
+    pub fn center(&self, cell: IVec2) -> Vec2 {
+        self.origin + (cell.as_vec2() + 0.5) * CELL_SIZE
+    }
+
+    pub fn contains(&self, cell: IVec2) -> bool {
+        cell.cmpge(IVec2::ZERO).all() && cell.cmplt(self.size).all()
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool {
+        self.contains(cell) && !self.blocked[self.index(cell)]
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.size.x + cell.x) as usize
+    }
+
+    /// Neighbours of `cell` and the cost of stepping to them.
+    ///
+    /// Diagonal steps are skipped when they would cut the corner of a wall.
+    fn neighbours(&self, cell: IVec2) -> impl Iterator<Item = (IVec2, u32)> + '_ {
+        (-1..=1)
+            .flat_map(|y| (-1..=1).map(move |x| IVec2::new(x, y)))
+            .filter(|offset| *offset != IVec2::ZERO)
+            .filter_map(move |offset| {
+                let neighbour = cell + offset;
+                if !self.is_walkable(neighbour) {
+                    return None;
+                }
+                if offset.x != 0 && offset.y != 0 {
+                    let cuts_corner = !self.is_walkable(cell + IVec2::new(offset.x, 0))
+                        || !self.is_walkable(cell + IVec2::new(0, offset.y));
+                    (!cuts_corner).then_some((neighbour, DIAGONAL_COST))
+                } else {
+                    Some((neighbour, STRAIGHT_COST))
+                }
+            })
+    }
+}
+
+fn bake_nav_grid(
+    mut grid: ResMut<NavGrid>,
+    colliders: Query<(Entity, &Collider, &CollisionLayers, Ref<GlobalTransform>)>,
+    mut removed: RemovedComponents<Collider>,
+) {
+    let walls = colliders
+        .iter()
+        .filter(|(_, _, layers, _)| layers.memberships.has_all(Layer::Wall))
+        .collect::<Vec<_>>();
+    let mut despawned = false;
+    for entity in removed.read() {
+        despawned |= grid.walls.contains(&entity);
+    }
+    let changed = walls
+        .iter()
+        .any(|(entity, _, _, transform)| transform.is_changed() || !grid.walls.contains(entity));
+    if !despawned && !changed {
+        return;
+    }
+
+    let entities = walls.iter().map(|(entity, ..)| *entity).collect();
+    let aabbs = walls
+        .iter()
+        .map(|(_, collider, _, transform)| {
+            let rotation = transform.rotation().to_euler(EulerRot::ZYX).0;
+            collider.aabb(transform.translation().xy(), Rotation::radians(rotation))
+        })
+        .collect::<Vec<_>>();
+    if aabbs.is_empty() {
+        *grid = NavGrid::default();
+        return;
+    }
+
+    let min = aabbs.iter().fold(Vec2::MAX, |min, aabb| min.min(aabb.min));
+    let max = aabbs.iter().fold(Vec2::MIN, |max, aabb| max.max(aabb.max));
+    let origin = min - CELL_SIZE;
+    let size = ((max - origin) / CELL_SIZE).ceil().as_ivec2() + 1;
+    let mut blocked = vec![false; (size.x * size.y) as usize];
+    for aabb in aabbs.iter() {
+        // Cells whose centers fall within the wall, grown by the clearance.
+        let first = ((aabb.min - CLEARANCE - origin) / CELL_SIZE - 0.5)
+            .ceil()
+            .as_ivec2();
+        let last = ((aabb.max + CLEARANCE - origin) / CELL_SIZE - 0.5)
+            .floor()
+            .as_ivec2();
+        let first = first.max(IVec2::ZERO);
+        let last = last.min(size - 1);
+        for y in first.y..=last.y {
+            for x in first.x..=last.x {
+                blocked[(y * size.x + x) as usize] = true;
+            }
+        }
+    }
+
+    *grid = NavGrid {
+        origin,
+        size,
+        blocked,
+        walls: entities,
+    };
+}
+
+/// Directions toward a single target from every cell of the [`NavGrid`].
+pub struct FlowField {
+    /// Position of the target when the field was planned.
+    goal: Vec2,
+    /// Path cost from each cell to the goal, `u32::MAX` if unreachable.
+    costs: Vec<u32>,
+}
+
+impl FlowField {
+    fn new(grid: &NavGrid, goal: Vec2) -> Option<Self> {
+        let goal_cell = grid.cell(goal)?;
+        let mut costs = vec![u32::MAX; grid.blocked.len()];
+        costs[grid.index(goal_cell)] = 0;
+
+        let mut open = BinaryHeap::from([(Reverse(0), goal_cell.to_array())]);
+        while let Some((Reverse(cost), cell)) = open.pop() {
+            let cell = IVec2::from_array(cell);
+            if cost > costs[grid.index(cell)] {
+                continue;
+            }
+            for (neighbour, step) in grid.neighbours(cell) {
+                let index = grid.index(neighbour);
+                if cost + step < costs[index] {
+                    costs[index] = cost + step;
+                    open.push((Reverse(cost + step), neighbour.to_array()));
+                }
+            }
+        }
+
+        Some(Self { goal, costs })
+    }
+
+    /// Direction to steer from `position` to follow the field.
+    ///
+    /// Returns [`None`] in the goal's cell and where the goal can not be reached,
+    /// in which case the target should be steered at directly.
+    pub fn direction(&self, grid: &NavGrid, position: Vec2) -> Option<Vec2> {
+        let cell = grid.cell(position)?;
+        let cost = self.costs[grid.index(cell)];
+        if cost == 0 {
+            return None;
+        }
+
+        let next = if grid.is_walkable(cell) {
+            grid.neighbours(cell)
+                .map(|(neighbour, _)| neighbour)
+                .min_by_key(|neighbour| self.costs[grid.index(*neighbour)])
+        } else {
+            // Cells hugging a wall are blocked, so step back out through any neighbour.
+            (-1..=1)
+                .flat_map(|y| (-1..=1).map(move |x| cell + IVec2::new(x, y)))
+                .filter(|neighbour| grid.is_walkable(*neighbour))
+                .min_by_key(|neighbour| self.costs[grid.index(*neighbour)])
+        }?;
+        let next_cost = self.costs[grid.index(next)];
+        (next_cost < cost).then(|| (grid.center(next) - position).normalize_or_zero())
+    }
+}
+
+/// A [`FlowField`] for every entity that is a [`SteerTarget`].
+///
+/// Shared by all steering entities chasing the same target.
+#[derive(Default, Resource)]
+pub struct FlowFields(pub EntityHashMap<FlowField>);
+
+impl FlowFields {
+    /// Direction to steer from `position` toward `target`, if a path was planned.
+    pub fn direction(&self, grid: &NavGrid, target: Entity, position: Vec2) -> Option<Vec2> {
+        self.0.get(&target)?.direction(grid, position)
+    }
+}
+
+fn update_flow_fields(
+    grid: Res<NavGrid>,
+    mut fields: ResMut<FlowFields>,
+    steering: Query<&SteerTarget>,
+    targets: Query<&GlobalTransform>,
+) {
+    let chased = steering
+        .iter()
+        .map(|steer_target| steer_target.0)
+        .collect::<EntityHashSet>();
+    fields.0.retain(|target, _| chased.contains(target));
+
+    for target in chased {
+        let Ok(transform) = targets.get(target) else {
+            fields.0.remove(&target);
+            continue;
+        };
+        plan(&mut fields.0, &grid, target, transform.translation().xy());
+    }
+}
+
+/// A [`FlowField`] toward the last sighting of every entity [`Awareness::Searching`].
+#[derive(Default, Resource)]
+pub struct SearchFields(pub EntityHashMap<FlowField>);
+
+impl SearchFields {
+    /// Direction to steer `searcher` from `position` toward where it last saw its target.
+    pub fn direction(&self, grid: &NavGrid, searcher: Entity, position: Vec2) -> Option<Vec2> {
+        self.0.get(&searcher)?.direction(grid, position)
+    }
+}
+
+fn update_search_fields(
+    grid: Res<NavGrid>,
+    mut fields: ResMut<SearchFields>,
+    searchers: Query<(Entity, &Awareness)>,
+) {
+    let searching = searchers
+        .iter()
+        .filter_map(|(searcher, awareness)| match awareness {
+            Awareness::Searching { last_seen, .. } => Some((searcher, *last_seen)),
+            _ => None,
+        })
+        .collect::<EntityHashMap<_>>();
+    fields
+        .0
+        .retain(|searcher, _| searching.contains_key(searcher));
+
+    for (searcher, last_seen) in searching {
+        plan(&mut fields.0, &grid, searcher, last_seen);
+    }
+}
+
+/// Re-plans the field under `key` unless its goal is still close to `goal` on the same grid.
+fn plan(fields: &mut EntityHashMap<FlowField>, grid: &Res<NavGrid>, key: Entity, goal: Vec2) {
+    let planned = fields
+        .get(&key)
+        .is_some_and(|field| field.goal.distance(goal) < REPLAN_DISTANCE);
+    if planned && !grid.is_changed() {
+        return;
+    }
+    match FlowField::new(grid, goal) {
+        Some(field) => {
+            fields.insert(key, field);
+        }
+        None => {
+            fields.remove(&key);
+        }
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    bits::{
+        self, BitEvent, BitSource,
+        coalescence::{Absorber, EnemyAbsorber, MASS_THRESOLD},
+    },
+    faction::Faction,
+    health::{DeathEvent, DeathSystems, EnemyHurtbox, MaxHealth},
+};
+use avian2d::prelude::*;
+use bevy::{color::palettes::css::PURPLE, prelude::*};
+use std::f32::consts::TAU;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, grow_nests)
+        .add_systems(FixedPostUpdate, release_bits.in_set(DeathSystems::Prepare))
+        .add_observer(spawn_nest);
+}
+
+const NEST_SIZE: f32 = 40.0;
+
+/// Mass with which a nest pulls in the bits assigned to it.
+const NEST_MASS: f32 = 20.0;
+
+/// Scale gained by a nest as its absorbed bits approach [`MASS_THRESOLD`].
+const NEST_GROWTH: f32 = 0.75;
+
+/// A destructible [`EnemyAbsorber`].
+///
+/// Nests grow as they absorb bits, and coalesce an enemy every [`MASS_THRESOLD`] bits.
+/// Destroying one releases the bits it has stored.
+#[derive(Component)]
+#[require(
+    Absorber = Absorber::new(NEST_MASS),
+    EnemyAbsorber,
+    MaxHealth(8.0),
+    Faction::Horde,
+    RigidBody::Static,
+    Collider = Collider::circle(NEST_SIZE / 2.0),
+    Sprite::from_color(PURPLE, Vec2::splat(NEST_SIZE)),
+    Name::new("Nest"),
+)]
+pub struct Nest;
+
+fn spawn_nest(trigger: On<Add, Nest>, mut commands: Commands) {
+    commands
+        .entity(trigger.entity)
+        .observe(bits::produce_bits)
+        .with_child((
+            EnemyHurtbox,
+            Collider::circle(NEST_SIZE / 2.0),
+            Transform::default(),
+        ));
+}
+
+fn grow_nests(mut nests: Query<(&mut Transform, &Absorber), (With<Nest>, Changed<Absorber>)>) {
+    for (mut transform, absorber) in nests.iter_mut() {
+        let progress = (absorber.bits_absorbed / MASS_THRESOLD).clamp(0.0, 1.0);
+        transform.scale = Vec3::splat(1.0 + progress * NEST_GROWTH);
+    }
+}
+
+fn release_bits(
+    mut reader: MessageReader<DeathEvent>,
+    mut writer: MessageWriter<BitEvent>,
+    nests: Query<(&GlobalTransform, &Absorber), With<Nest>>,
+) {
+    for event in reader.read() {
+        let Ok((transform, absorber)) = nests.get(event.0) else {
+            continue;
+        };
+        // Each source bursts out in its own direction.
+        for (i, source) in BitSource::ALL.into_iter().enumerate() {
+            writer.write(BitEvent {
+                direction: Vec2::from_angle(TAU * i as f32 / BitSource::ALL.len() as f32),
+                translation: transform.translation().xy(),
+                bits: absorber.composition.get(source).round() as usize,
+                source,
+            });
+        }
+    }
+}
@@ -1,12 +1,25 @@
 use super::Player;
 use crate::{
     Layer,
-    bits::BitEvent,
-    enemy::{EnableAttacks, FinisherTarget},
+    bits::{BitEvent, BitSource},
+    block::{Block, Blocking},
+    enemy::{EnableAttacks, FinisherDamage, FinisherTarget, Staggered},
+    faction::{Faction, FactionRelations},
+    feedback::Cinematic,
     health::{CurrentHealth, DeathEvent, FriendlyHitbox},
     physics::velocity,
-    player::{OrientationMethod, PlayerHurtbox},
-    weapon::{TriggerWeapon, Weapon, WeaponPickup},
+    player::{
+        OrientationMethod, PlayerHurtbox,
+        stats::{self, Stat, Stats},
+    },
+    shop::{self, Offer},
+    stamina::Stamina,
+    status::Frozen,
+    weapon::{
+        self, HitEvent, ReloadWeapon, TriggerWeapon, Weapon, WeaponPickup, Wielder,
+        charge::{ChargeAttack, Charging},
+        finisher::Finisher,
+    },
 };
 use avian2d::prelude::*;
 use bevy::prelude::*;
@@ -24,11 +37,17 @@ pub struct InputPlugin;
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_input_context::<Player>()
-            .add_systems(Update, (end_dash, end_finish))
+            .add_systems(Update, (end_dash, strike_finisher, end_finish))
             .add_observer(inject_bindings)
             .add_observer(apply_movement)
             .add_observer(stop_movement)
             .add_observer(handle_attack)
+            .add_observer(start_charge)
+            .add_observer(cancel_charge)
+            .add_observer(release_charge)
+            .add_observer(handle_reload)
+            .add_observer(start_block)
+            .add_observer(end_block)
             .add_observer(handle_dash)
             .add_observer(handle_pick_up)
             .add_observer(handle_throw)
@@ -64,6 +83,20 @@ fn inject_bindings(trigger: On<Insert, Player>, mut commands: Commands) {
             Press::default(),
             bindings![KeyCode::Space, GamepadButton::West, GamepadButton::RightTrigger2, MouseButton::Left],
         ),
+        (
+            Action::<Charge>::new(),
+            Hold::new(CHARGE_HOLD_SECS),
+            bindings![KeyCode::Space, GamepadButton::West, GamepadButton::RightTrigger2, MouseButton::Left],
+        ),
+        (
+            Action::<Reload>::new(),
+            Press::default(),
+            bindings![KeyCode::KeyR, GamepadButton::DPadDown],
+        ),
+        (
+            Action::<Defend>::new(),
+            bindings![KeyCode::KeyQ, MouseButton::Right, GamepadButton::LeftTrigger],
+        ),
         (
             Action::<Dash>::new(),
             Press::default(),
@@ -146,9 +179,118 @@ struct Attack;
 fn handle_attack(
     _attack: On<Fire<Attack>>,
     mut commands: Commands,
+    player: Single<(Entity, Option<&Children>), With<Player>>,
+    charge_weapons: Query<Entity, (With<Weapon>, With<ChargeAttack>)>,
+) {
+    let (player, children) = player.into_inner();
+    // Charge weapons attack when the action is released.
+    if children.is_some_and(|children| charge_weapons.iter_many(children).next().is_some()) {
+        return;
+    }
+    commands.entity(player).trigger(TriggerWeapon::friendly);
+}
+
+/// Time the attack action must be held before a [`ChargeAttack`] starts charging.
+const CHARGE_HOLD_SECS: f32 = 0.15;
+
+#[derive(InputAction)]
+#[action_output(bool)]
+struct Charge;
+
+fn start_charge(
+    _charge: On<Fire<Charge>>,
+    mut commands: Commands,
+    player: Single<&Children, With<Player>>,
+    charge_weapons: Query<Entity, (With<Weapon>, With<ChargeAttack>)>,
+) {
+    if let Some(weapon) = charge_weapons.iter_many(*player).next() {
+        commands
+            .entity(weapon)
+            .insert_if_new(Charging(CHARGE_HOLD_SECS));
+    }
+}
+
+fn cancel_charge(
+    _charge: On<Cancel<Charge>>,
+    mut commands: Commands,
+    player: Single<(Entity, &Children), With<Player>>,
+    charge_weapons: Query<Entity, (With<Weapon>, With<ChargeAttack>)>,
+) {
+    let (player, children) = player.into_inner();
+    if charge_weapons.iter_many(children).next().is_some() {
+        commands.entity(player).trigger(TriggerWeapon::friendly);
+    }
+}
+
+/// Stamina spent by a fully charged attack.
+const CHARGE_STAMINA_COST: f32 = 30.0;
+
+/// Releases a charged attack, weakened by however much stamina is missing.
+fn release_charge(
+    _charge: On<Complete<Charge>>,
+    mut commands: Commands,
+    player: Single<(Entity, &Children, &mut Stamina), With<Player>>,
+    charge_weapons: Query<(Entity, &ChargeAttack, Option<&Charging>), With<Weapon>>,
+) {
+    let (player, children, mut stamina) = player.into_inner();
+    if let Some((weapon, attack, charging)) = charge_weapons.iter_many(children).next() {
+        commands.entity(weapon).remove::<Charging>();
+        let charge = charging.map(|charging| {
+            let charge = charging.charge(attack);
+            charge * stamina.drain(CHARGE_STAMINA_COST * charge)
+        });
+        match charge {
+            Some(charge) if charge >= attack.min_charge => {
+                commands
+                    .entity(player)
+                    .trigger(move |entity| TriggerWeapon::friendly(entity).with_charge(charge));
+            }
+            _ => {
+                commands.entity(player).trigger(TriggerWeapon::friendly);
+            }
+        }
+    }
+}
+
+#[derive(InputAction)]
+#[action_output(bool)]
+struct Reload;
+
+fn handle_reload(
+    _reload: On<Fire<Reload>>,
+    mut commands: Commands,
+    player: Single<Entity, With<Player>>,
+) {
+    commands.entity(*player).trigger(ReloadWeapon::new);
+}
+
+#[derive(InputAction)]
+#[action_output(bool)]
+struct Defend;
+
+/// Stamina spent to raise a block.
+const BLOCK_STAMINA_COST: f32 = 10.0;
+
+fn start_block(
+    _defend: On<Start<Defend>>,
+    mut commands: Commands,
+    player: Single<
+        (Entity, &Block, &mut Stamina),
+        (With<Player>, Without<Dashing>, Without<Finishing>),
+    >,
+) {
+    let (player, block, mut stamina) = player.into_inner();
+    if stamina.try_spend(BLOCK_STAMINA_COST) {
+        commands.entity(player).insert(Blocking::new(block));
+    }
+}
+
+fn end_block(
+    _defend: On<Complete<Defend>>,
+    mut commands: Commands,
     player: Single<Entity, With<Player>>,
 ) {
-    commands.entity(*player).trigger(TriggerWeapon::friendly);
+    commands.entity(*player).remove::<Blocking>();
 }
 
 #[derive(InputAction)]
@@ -158,21 +300,33 @@ struct Dash;
 #[derive(Component)]
 pub struct Dashing;
 
+/// Stamina spent by a dash.
+const DASH_STAMINA_COST: f32 = 35.0;
+
 fn handle_dash(
     _dash: On<Fire<Dash>>,
     mut commands: Commands,
-    player: Single<(Entity, &RetainedMove), Without<Finishing>>,
+    player: Single<
+        (Entity, &RetainedMove, &mut Stamina, &Stats),
+        (Without<Dashing>, Without<Finishing>),
+    >,
     hurtbox: Single<Entity, With<PlayerHurtbox>>,
 ) {
-    let (player_entity, last_input) = player.into_inner();
-    commands.entity(player_entity).insert(Dashing);
-    let start = last_input.0 * 1_000.0;
-    let end = last_input.0 * Player::MAX_SPEED.0 / 2.0;
+    let (player_entity, last_input, mut stamina, stats) = player.into_inner();
+    if !stamina.try_spend(DASH_STAMINA_COST) {
+        return;
+    }
+    commands
+        .entity(player_entity)
+        .insert(Dashing)
+        .remove::<Blocking>();
+    let start = last_input.0 * stats.get(Stat::DashSpeed);
+    let end = last_input.0 * stats.get(Stat::MaxSpeed) / 2.0;
 
     let animation = commands
         .animation()
         .insert_tween_here(
-            Duration::from_secs_f32(0.4),
+            Duration::from_secs_f32(stats.get(Stat::DashDuration)),
             EaseKind::QuarticOut,
             player_entity.into_target().with(velocity(start, end)),
         )
@@ -190,15 +344,19 @@ fn end_dash(
     mut commands: Commands,
     dashing: Query<&Dashing>,
     mut ended: MessageReader<TimeRunnerEnded>,
-    player: Single<Entity, With<Player>>,
+    player: Single<(Entity, &Stats, Option<&mut Frozen>), With<Player>>,
     hurtbox: Single<Entity, With<PlayerHurtbox>>,
 ) {
+    let (player, stats, mut frozen) = player.into_inner();
     for ended in ended.read() {
         if ended.is_completed() && dashing.contains(ended.entity) {
             commands.entity(ended.entity).despawn();
             commands
-                .entity(*player)
-                .insert((Player::LINEAR_DAMPING, Player::MAX_SPEED))
+                .entity(player)
+                .insert((
+                    LinearDamping(stats.get(Stat::LinearDamping)),
+                    stats::max_speed(stats, frozen.as_mut().map(|frozen| frozen.reborrow())),
+                ))
                 .remove::<Dashing>();
             commands.entity(*hurtbox).remove::<ColliderDisabled>();
         }
@@ -207,7 +365,7 @@ fn end_dash(
 
 #[derive(InputAction)]
 #[action_output(bool)]
-struct PickUp;
+pub struct PickUp;
 
 fn handle_pick_up(
     _pick_up: On<Fire<PickUp>>,
@@ -218,11 +376,20 @@ fn handle_pick_up(
         (With<Player>, Without<Dashing>, Without<Finishing>),
     >,
     weapons: Query<&Weapon>,
+    offers: Query<&GlobalTransform, With<Offer>>,
 ) {
     let (player_entity, player_transform, children) = player.into_inner();
     if children.iter().any(|c| weapons.contains(c)) {
         return;
     }
+    // The press buys the offer instead.
+    let player_translation = player_transform.translation().xy();
+    if offers
+        .iter()
+        .any(|offer| shop::within_reach(offer, player_translation))
+    {
+        return;
+    }
 
     for (entity, gt, pickup) in pickups.iter() {
         if gt
@@ -230,10 +397,7 @@ fn handle_pick_up(
             .distance_squared(player_transform.translation())
             <= pickup.0 * pickup.0
         {
-            commands
-                .entity(entity)
-                .remove::<(WeaponPickup, RigidBody)>()
-                .insert(ChildOf(player_entity));
+            weapon::equip(&mut commands, entity, player_entity);
             return;
         }
     }
@@ -247,12 +411,12 @@ fn handle_throw(
     _throw: On<Fire<Throw>>,
     mut commands: Commands,
     player: Single<
-        (&GlobalTransform, &Children),
+        (Entity, &GlobalTransform, &Children),
         (With<Player>, Without<Dashing>, Without<Finishing>),
     >,
     weapons: Query<Entity, With<Weapon>>,
 ) {
-    let (player_transform, children) = player.into_inner();
+    let (player, player_transform, children) = player.into_inner();
     let rotation = player_transform.rotation().to_euler(EulerRot::ZYX).0;
     let mut layers = FriendlyHitbox::collision_layers();
     layers.filters |= Layer::Wall.to_bits();
@@ -265,6 +429,8 @@ fn handle_throw(
                 LinearVelocity(Vec2::Y.rotate(Vec2::from_angle(rotation)) * 1000.0),
                 RigidBody::Dynamic,
                 FriendlyHitbox,
+                Faction::Player,
+                Wielder(player),
                 layers,
                 LinearDamping(3.5),
             ))
@@ -280,17 +446,34 @@ struct Finish;
 pub struct Finishing {
     direction: Vec2,
     target: Entity,
+    finisher: Finisher,
 }
 
+/// Strikes of a [`Finisher`] dealt before the one that lands it.
+#[derive(Component)]
+struct FinisherStrikes {
+    remaining: usize,
+    timer: Timer,
+}
+
+/// Knockback of a [`Finisher`]'s area damage, directed away from the target.
+const FINISHER_AREA_KNOCKBACK: f32 = 400.0;
+
+/// Finishes the closest [`FinisherTarget`] in range with the [`Finisher`] of
+/// the player's weapon.
 fn handle_finish(
     _finish: On<Fire<Finish>>,
     mut commands: Commands,
-    player: Single<(Entity, &GlobalTransform), (With<Player>, Without<Dashing>)>,
+    player: Single<
+        (Entity, &GlobalTransform, &Stats, Option<&Children>),
+        (With<Player>, Without<Dashing>),
+    >,
     hurtbox: Single<Entity, With<PlayerHurtbox>>,
     targets: Query<(Entity, &GlobalTransform), With<FinisherTarget>>,
+    weapons: Query<&Finisher, With<Weapon>>,
 ) {
-    let dist = 100.0;
-    let (player_entity, transform) = player.into_inner();
+    let (player_entity, transform, stats, children) = player.into_inner();
+    let dist = stats.get(Stat::FinisherRange);
     let start = transform.translation();
     let end = targets
         .iter()
@@ -304,16 +487,22 @@ fn handle_finish(
     if let Some((target, end)) = end
         && end.distance_squared(start.xy()) <= dist * dist
     {
+        let finisher = children
+            .and_then(|children| weapons.iter_many(children).next())
+            .copied()
+            .unwrap_or_default();
         let finishing = Finishing {
             direction: end - start.xy(),
             target,
+            finisher,
         };
 
+        let end = finisher.animation.end(start.xy(), end);
         let animation = commands
             .animation()
             .insert_tween_here(
-                Duration::from_secs_f32(0.1),
-                EaseKind::QuarticOut,
+                Duration::from_secs_f32(finisher.duration),
+                finisher.animation.ease(),
                 player_entity
                     .into_target()
                     .with(translation(start, end.extend(start.z))),
@@ -321,40 +510,173 @@ fn handle_finish(
             .insert(finishing)
             .id();
 
-        commands
-            .entity(player_entity)
-            .insert(finishing)
-            .add_child(animation);
+        let mut player = commands.entity(player_entity);
+        player.insert(finishing).add_child(animation);
+        if finisher.hits > 1 {
+            player.insert(FinisherStrikes {
+                remaining: finisher.hits - 1,
+                timer: Timer::from_seconds(
+                    finisher.duration / finisher.hits as f32,
+                    TimerMode::Repeating,
+                ),
+            });
+        }
+        if finisher.cinematic {
+            player.insert(Cinematic);
+        }
         commands.entity(*hurtbox).insert(ColliderDisabled);
         commands.entity(target).remove::<EnableAttacks>();
     }
 }
 
+/// A strike of a finisher or its [`FinisherArea`](crate::weapon::finisher::FinisherArea).
+///
+/// It is triggered on the root directly, bypassing the hit resolution of hurtboxes.
+fn finisher_hit(
+    target: Entity,
+    player: Entity,
+    damage: f32,
+    bits: usize,
+    source: BitSource,
+    translation: Vec2,
+) -> HitEvent {
+    HitEvent {
+        target,
+        attacker: None,
+        wielder: Some(player),
+        damage,
+        knockback: Vec2::ZERO,
+        bits,
+        source,
+        target_translation: translation,
+        attacker_translation: translation,
+        crit: false,
+    }
+}
+
+/// The bit source of the player's weapon.
+fn weapon_source(
+    children: Option<&Children>,
+    weapons: &Query<&BitSource, With<Weapon>>,
+) -> BitSource {
+    children
+        .and_then(|children| weapons.iter_many(children).next())
+        .copied()
+        .unwrap_or_default()
+}
+
+/// Deals the strikes leading up to the one that lands the finisher, each
+/// releasing a share of its bits.
+fn strike_finisher(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut player: Query<
+        (
+            Entity,
+            &Finishing,
+            &mut FinisherStrikes,
+            &GlobalTransform,
+            Option<&Children>,
+        ),
+        With<Player>,
+    >,
+    weapons: Query<&BitSource, With<Weapon>>,
+    finisher_damage: Query<&FinisherDamage>,
+    targets: Query<(), With<CurrentHealth>>,
+) {
+    for (player, finishing, mut strikes, transform, children) in player.iter_mut() {
+        let target = finishing.target;
+        let finisher = finishing.finisher;
+        if strikes.remaining == 0
+            || !strikes.timer.tick(time.delta()).just_finished()
+            || !targets.contains(target)
+        {
+            continue;
+        }
+        strikes.remaining -= 1;
+
+        let damage = finisher_damage
+            .get(target)
+            .map_or(0.0, |damage| damage.0 / finisher.hits as f32);
+        commands.trigger(finisher_hit(
+            target,
+            player,
+            damage,
+            finisher.bits / finisher.hits,
+            weapon_source(children, &weapons),
+            transform.translation().xy(),
+        ));
+    }
+}
+
 fn end_finish(
     mut commands: Commands,
     finishing: Query<&Finishing>,
     mut ended: MessageReader<TimeRunnerEnded>,
-    player: Single<(Entity, &GlobalTransform), With<Player>>,
+    player: Single<(Entity, &GlobalTransform, &Faction, Option<&Children>), With<Player>>,
     hurtbox: Single<Entity, With<PlayerHurtbox>>,
+    weapons: Query<&BitSource, With<Weapon>>,
     mut health: Query<&mut CurrentHealth>,
+    finisher_damage: Query<&FinisherDamage>,
+    roots: Query<(Entity, &GlobalTransform, &Faction), With<CurrentHealth>>,
+    relations: Res<FactionRelations>,
     mut death_writer: MessageWriter<DeathEvent>,
     mut bits_writer: MessageWriter<BitEvent>,
 ) -> Result {
-    let (player, player_transform) = player.into_inner();
+    let (player, player_transform, player_faction, children) = player.into_inner();
+    let source = weapon_source(children, &weapons);
     for ended in ended.read() {
         if ended.is_completed()
             && let Ok(target) = finishing.get(ended.entity)
         {
+            let finisher = target.finisher;
             commands.entity(ended.entity).despawn();
-            commands.entity(player).remove::<Finishing>();
+            commands
+                .entity(player)
+                .remove::<(Finishing, FinisherStrikes, Cinematic)>();
             commands.entity(*hurtbox).remove::<ColliderDisabled>();
-            let mut health = health.get_mut(target.target)?;
-            health.0 = 0.0;
-            death_writer.write(DeathEvent(target.target));
+            let translation = player_transform.translation().xy();
+
+            if let Some(area) = finisher.area
+                && let Ok((_, target_transform, _)) = roots.get(target.target)
+            {
+                let center = target_transform.translation().xy();
+                for (entity, transform, faction) in roots.iter() {
+                    let root_translation = transform.translation().xy();
+                    if entity == target.target
+                        || !relations.is_hostile(*player_faction, *faction)
+                        || root_translation.distance_squared(center) > area.radius * area.radius
+                    {
+                        continue;
+                    }
+                    commands.trigger(HitEvent {
+                        knockback: (root_translation - center).normalize_or_zero()
+                            * FINISHER_AREA_KNOCKBACK,
+                        target_translation: root_translation,
+                        attacker_translation: center,
+                        ..finisher_hit(entity, player, area.damage, 0, source, root_translation)
+                    });
+                }
+            }
+
+            if let Ok(damage) = finisher_damage.get(target.target) {
+                let damage = damage.0 / finisher.hits as f32;
+                commands
+                    .entity(target.target)
+                    .try_remove::<(FinisherTarget, Staggered)>()
+                    .trigger(|target| finisher_hit(target, player, damage, 0, source, translation));
+            } else {
+                let mut health = health.get_mut(target.target)?;
+                health.0 = 0.0;
+                death_writer.write(DeathEvent(target.target));
+            }
+            // Earlier strikes already released their share.
+            let struck = finisher.bits / finisher.hits * (finisher.hits - 1);
             bits_writer.write(BitEvent {
                 direction: target.direction,
-                translation: player_transform.translation().xy(),
-                bits: 15,
+                translation,
+                bits: finisher.bits - struck,
+                source,
             });
         }
     }
@@ -1,22 +1,29 @@
 use crate::{
     Layer,
-    player::input::{Dashing, Finishing, RetainedMove},
-};
-use avian2d::prelude::{
-    Collider, CollisionLayers, LinearDamping, LockedAxes, MaxLinearSpeed, RigidBody,
+    bits::coalescence::BitMagnet,
+    block::Block,
+    faction::Faction,
+    player::{
+        input::{Dashing, Finishing, RetainedMove},
+        stats::{BaseStats, PLAYER_STATS, Perks, Stats},
+    },
+    stamina::Stamina,
+    weapon::{AmmoPickup, ReserveAmmo, Weapon},
 };
+use avian2d::prelude::{CollisionLayers, LockedAxes, RigidBody};
 use bevy::{
     color::palettes::css::BLUE, input::mouse::MouseMotion, prelude::*, window::PrimaryWindow,
 };
 
 pub mod input;
+pub mod stats;
 
 pub struct PlayerPlugin;
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins(input::InputPlugin)
-            .add_systems(Update, orient_player_with_mouse_input);
+        app.add_plugins((input::InputPlugin, stats::plugin))
+            .add_systems(Update, (orient_player_with_mouse_input, collect_ammo));
     }
 }
 
@@ -28,18 +35,21 @@ impl Plugin for PlayerPlugin {
     Sprite::from_color(BLUE, Vec2::new(20.0, 20.0)),
     Name::new("Player"),
     CollisionLayers = Self::collision_layers(),
-    Collider::circle(7.5),
     LockedAxes::ROTATION_LOCKED,
+    Faction::Player,
     OrientationMethod,
-    LinearDamping = Self::LINEAR_DAMPING,
-    MaxLinearSpeed = Self::MAX_SPEED,
-    RetainedMove
+    // Inserts the collider, damping and max speed.
+    BaseStats = BaseStats::new(PLAYER_STATS),
+    Stats,
+    Perks,
+    RetainedMove,
+    Block,
+    Stamina,
+    BitMagnet
 )]
 pub struct Player;
 
 impl Player {
-    pub const LINEAR_DAMPING: LinearDamping = LinearDamping(100.0);
-    pub const MAX_SPEED: MaxLinearSpeed = MaxLinearSpeed(200.0);
     pub fn collision_layers() -> CollisionLayers {
         CollisionLayers::new(Layer::Empty, Layer::Wall)
     }
@@ -87,3 +97,27 @@ fn orient_player_with_mouse_input(
         }
     }
 }
+
+fn collect_ammo(
+    mut commands: Commands,
+    player: Single<(&GlobalTransform, &Children), With<Player>>,
+    pickups: Query<(Entity, &GlobalTransform, &AmmoPickup)>,
+    mut reserves: Query<&mut ReserveAmmo, With<Weapon>>,
+) {
+    let (player_transform, children) = player.into_inner();
+    let mut iter = reserves.iter_many_mut(children);
+    let Some(mut reserve) = iter.fetch_next() else {
+        return;
+    };
+
+    for (entity, gt, pickup) in pickups.iter() {
+        if gt
+            .translation()
+            .distance_squared(player_transform.translation())
+            <= pickup.radius * pickup.radius
+        {
+            reserve.0 += pickup.rounds;
+            commands.entity(entity).despawn();
+        }
+    }
+}
@@ -0,0 +1,266 @@
+use super::input::Dashing;
+use crate::{
+    bits::coalescence::BitMagnet,
+    health::{CurrentHealth, MaxHealth},
+    stamina::Stamina,
+    status::Frozen,
+};
+use avian2d::prelude::{Collider, LinearDamping, MaxLinearSpeed};
+use bevy::{platform::collections::HashMap, prelude::*};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(PreUpdate, apply_stats);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stat {
+    MaxSpeed,
+    LinearDamping,
+    /// Radius of the body collider.
+    Radius,
+    /// Initial speed of a dash.
+    DashSpeed,
+    /// Seconds that a dash lasts.
+    DashDuration,
+    /// Distance from which a finisher can reach its target.
+    FinisherRange,
+    MaxHealth,
+    MaxStamina,
+    MagnetRadius,
+    /// Health restored by every collected bit.
+    BitHeal,
+}
+
+/// Base stats of the player before any [`Perk`].
+pub const PLAYER_STATS: &[(Stat, f32)] = &[
+    (Stat::MaxSpeed, 200.0),
+    (Stat::LinearDamping, 100.0),
+    (Stat::Radius, 7.5),
+    (Stat::DashSpeed, 1_000.0),
+    (Stat::DashDuration, 0.4),
+    (Stat::FinisherRange, 100.0),
+    (Stat::MaxHealth, 10.0),
+    (Stat::MaxStamina, 100.0),
+    (Stat::MagnetRadius, 120.0),
+    (Stat::BitHeal, 0.0),
+];
+
+#[derive(Component)]
+pub struct BaseStats(pub HashMap<Stat, f32>);
+
+impl BaseStats {
+    pub fn new(stats: &[(Stat, f32)]) -> Self {
+        Self(stats.iter().copied().collect())
+    }
+}
+
+/// [`BaseStats`] with every [`Perk`] applied.
+///
+/// Recomputed into the live components whenever the base stats or perks change.
+#[derive(Default, Component)]
+pub struct Stats(HashMap<Stat, f32>);
+
+impl Stats {
+    pub fn get(&self, stat: Stat) -> f32 {
+        self.0.get(&stat).copied().unwrap_or_default()
+    }
+
+    fn compute(base: &BaseStats, perks: &Perks) -> Self {
+        let mut stats = base.0.clone();
+        let mut modifiers = perks
+            .0
+            .iter()
+            .flat_map(|perk| perk.modifiers)
+            .collect::<Vec<_>>();
+        modifiers.sort_by_key(|modifier| modifier.priority);
+        for modifier in modifiers {
+            let value = stats.entry(modifier.stat).or_default();
+            match modifier.op {
+                Op::Add => *value += modifier.value,
+                Op::Multiply => *value *= modifier.value,
+            }
+        }
+        Self(stats)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Op {
+    Add,
+    Multiply,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct StatModifier {
+    pub stat: Stat,
+    pub op: Op,
+    pub value: f32,
+    /// Modifiers apply from lowest to highest priority.
+    pub priority: i32,
+}
+
+impl StatModifier {
+    /// Adds `value` before any multipliers.
+    pub const fn add(stat: Stat, value: f32) -> Self {
+        Self {
+            stat,
+            op: Op::Add,
+            value,
+            priority: 0,
+        }
+    }
+
+    /// Multiplies by `value` after any additions.
+    pub const fn multiply(stat: Stat, value: f32) -> Self {
+        Self {
+            stat,
+            op: Op::Multiply,
+            value,
+            priority: 1,
+        }
+    }
+
+    pub const fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+}
+
+/// A passive upgrade that modifies [`Stats`].
+#[derive(Debug)]
+pub struct Perk {
+    pub name: &'static str,
+    pub modifiers: &'static [StatModifier],
+}
+
+pub const LONG_DASH: Perk = Perk {
+    name: "Longer dash",
+    modifiers: &[StatModifier::multiply(Stat::DashDuration, 1.5)],
+};
+
+pub const LONG_FINISHER: Perk = Perk {
+    name: "Finisher range +50%",
+    modifiers: &[StatModifier::multiply(Stat::FinisherRange, 1.5)],
+};
+
+pub const BITS_HEAL: Perk = Perk {
+    name: "Bits heal",
+    modifiers: &[StatModifier::add(Stat::BitHeal, 0.1)],
+};
+
+pub const VITALITY: Perk = Perk {
+    name: "Max health +2",
+    modifiers: &[StatModifier::add(Stat::MaxHealth, 2.0)],
+};
+
+pub const ENDURANCE: Perk = Perk {
+    name: "Max stamina +25",
+    modifiers: &[StatModifier::add(Stat::MaxStamina, 25.0)],
+};
+
+pub const MAGNETISM: Perk = Perk {
+    name: "Magnet +40",
+    modifiers: &[StatModifier::add(Stat::MagnetRadius, 40.0)],
+};
+
+/// Perks owned by the entity.
+#[derive(Default, Component)]
+pub struct Perks(pub Vec<&'static Perk>);
+
+/// The [`MaxLinearSpeed`] granted by `stats`, kept slowed while [`Frozen`].
+pub fn max_speed(stats: &Stats, frozen: Option<Mut<Frozen>>) -> MaxLinearSpeed {
+    let speed = stats.get(Stat::MaxSpeed);
+    match frozen {
+        Some(mut frozen) => frozen.slow(speed),
+        None => MaxLinearSpeed(speed),
+    }
+}
+
+fn apply_stats(
+    mut commands: Commands,
+    mut entities: Query<
+        (
+            Entity,
+            &BaseStats,
+            &Perks,
+            &mut Stats,
+            Option<(&mut MaxHealth, &mut CurrentHealth)>,
+            Option<&mut Stamina>,
+            Option<&mut BitMagnet>,
+            (Has<Dashing>, Option<&mut Frozen>),
+        ),
+        Or<(Changed<BaseStats>, Changed<Perks>)>,
+    >,
+) {
+    for (entity, base, perks, mut stats, health, stamina, magnet, (dashing, frozen)) in
+        entities.iter_mut()
+    {
+        *stats = Stats::compute(base, perks);
+
+        let mut entity = commands.entity(entity);
+        entity.insert(Collider::circle(stats.get(Stat::Radius)));
+        // Dashes remove these and restore them from `Stats` once finished.
+        if !dashing {
+            entity.insert((
+                LinearDamping(stats.get(Stat::LinearDamping)),
+                max_speed(&stats, frozen),
+            ));
+        }
+
+        if let Some((mut max, mut current)) = health {
+            let max_health = stats.get(Stat::MaxHealth);
+            let gained = (max_health - max.0).max(0.0);
+            current.0 = (current.0 + gained).min(max_health);
+            max.0 = max_health;
+        }
+        if let Some(mut stamina) = stamina {
+            let max_stamina = stats.get(Stat::MaxStamina);
+            let gained = (max_stamina - stamina.max).max(0.0);
+            stamina.current = (stamina.current + gained).min(max_stamina);
+            stamina.max = max_stamina;
+        }
+        if let Some(mut magnet) = magnet {
+            magnet.radius = stats.get(Stat::MagnetRadius);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ADD: Perk = Perk {
+        name: "Add",
+        modifiers: &[StatModifier::add(Stat::MaxSpeed, 10.0)],
+    };
+
+    const DOUBLE: Perk = Perk {
+        name: "Double",
+        modifiers: &[StatModifier::multiply(Stat::MaxSpeed, 2.0)],
+    };
+
+    const DOUBLE_FIRST: Perk = Perk {
+        name: "Double first",
+        modifiers: &[StatModifier::multiply(Stat::MaxSpeed, 2.0).with_priority(-1)],
+    };
+
+    #[test]
+    fn additions_apply_before_multipliers() {
+        let base = BaseStats::new(&[(Stat::MaxSpeed, 5.0)]);
+        let stats = Stats::compute(&base, &Perks(vec![&DOUBLE, &ADD]));
+        assert_eq!(stats.get(Stat::MaxSpeed), 30.0);
+    }
+
+    #[test]
+    fn lower_priorities_apply_first() {
+        let base = BaseStats::new(&[(Stat::MaxSpeed, 5.0)]);
+        let stats = Stats::compute(&base, &Perks(vec![&ADD, &DOUBLE_FIRST]));
+        assert_eq!(stats.get(Stat::MaxSpeed), 20.0);
+    }
+
+    #[test]
+    fn missing_base_stats_start_at_zero() {
+        let stats = Stats::compute(&BaseStats::new(&[]), &Perks(vec![&BITS_HEAL]));
+        assert_eq!(stats.get(Stat::BitHeal), 0.1);
+    }
+}
@@ -0,0 +1,111 @@
+use avian2d::prelude::*;
+use bevy::{ecs::system::SystemParam, platform::collections::HashMap, prelude::*};
+use std::any::TypeId;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<EntityPools>();
+}
+
+/// A prefab whose instances are recycled through an [`EntityPool`].
+///
+/// The prefab marker is the key of its pool.
+pub trait Prefab: Component {
+    /// Removes state that should not carry over to the next use.
+    fn reset(_entity: &mut EntityWorldMut) {}
+}
+
+/// Inactive entities for each [`Prefab`].
+#[derive(Default, Resource)]
+pub struct EntityPools(HashMap<TypeId, Vec<Entity>>);
+
+/// An entity that returns to its pool instead of despawning.
+#[derive(Clone, Copy, Component)]
+pub struct Pooled {
+    prefab: TypeId,
+    reset: fn(&mut EntityWorldMut),
+}
+
+impl Pooled {
+    pub fn new<P: Prefab>() -> Self {
+        Self {
+            prefab: TypeId::of::<P>(),
+            reset: P::reset,
+        }
+    }
+}
+
+/// A pooled entity waiting to be reused.
+///
+/// Its collider, rigid body and sprite are disabled until it is spawned again.
+#[derive(Component)]
+pub struct Inactive;
+
+/// Spawns and despawns [`Prefab`] instances without churning through entities.
+#[derive(SystemParam)]
+pub struct EntityPool<'w, 's> {
+    commands: Commands<'w, 's>,
+    pools: ResMut<'w, EntityPools>,
+    pooled: Query<'w, 's, Has<Inactive>, With<Pooled>>,
+}
+
+impl EntityPool<'_, '_> {
+    /// Reactivates an inactive instance of `P` with `bundle`, or spawns a new one.
+    pub fn spawn<P: Prefab>(&mut self, bundle: impl Bundle) -> EntityCommands<'_> {
+        let free = self.pools.0.entry(TypeId::of::<P>()).or_default();
+        while let Some(entity) = free.pop() {
+            // Pooled entities can still be despawned by their parents.
+            if self.pooled.get(entity).is_ok_and(|inactive| inactive) {
+                let mut entity = self.commands.entity(entity);
+                entity
+                    .remove::<(Inactive, ColliderDisabled, RigidBodyDisabled)>()
+                    .insert((Visibility::Inherited, bundle));
+                return entity;
+            }
+        }
+
+        self.commands.spawn((Pooled::new::<P>(), bundle))
+    }
+
+    /// Returns a [`Pooled`] entity to its pool, despawning anything else.
+    pub fn despawn(&mut self, entity: Entity) {
+        match self.pooled.get(entity) {
+            Ok(true) => {}
+            Ok(false) => {
+                self.commands.queue(release(entity));
+            }
+            Err(_) => {
+                self.commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+fn release(entity: Entity) -> impl Command {
+    move |world: &mut World| {
+        let Ok(mut entity_mut) = world.get_entity_mut(entity) else {
+            return;
+        };
+        // Released more than once in a frame.
+        if entity_mut.contains::<Inactive>() {
+            return;
+        }
+        let Some(pooled) = entity_mut.get::<Pooled>().copied() else {
+            return;
+        };
+
+        (pooled.reset)(&mut entity_mut);
+        entity_mut.insert((
+            Inactive,
+            ColliderDisabled,
+            RigidBodyDisabled,
+            Visibility::Hidden,
+            LinearVelocity::ZERO,
+        ));
+        world
+            .resource_mut::<EntityPools>()
+            .0
+            .entry(pooled.prefab)
+            .or_default()
+            .push(entity);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::{
+    HEIGHT, Layer, WIDTH,
+    bits::BitSource,
+    faction::Faction,
+    health::{EnemyHitbox, EnemyHurtbox, FriendlyHitbox, FriendlyHurtbox, Hitbox},
+    pool::{EntityPool, Inactive, Prefab},
+    status::OnHitEffects,
+    weapon::{AlreadyHit, Critical, Damage, DecrementDurabilityOnHit, MultiHit, Weapon, Wielder},
+};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (
+            homing,
+            orient_projectiles,
+            projectile_lifetime,
+            despawn_offscreen,
+        ),
+    )
+    .add_observer(projectile_wall_impact);
+}
+
+/// Marks an attack that travels through the world as a rigid body.
+#[derive(Component)]
+#[require(
+    RigidBody::Dynamic,
+    GravityScale(0.0),
+    LockedAxes::ROTATION_LOCKED,
+    Restitution = Restitution::new(1.0).with_combine_rule(CoefficientCombine::Max),
+    Friction = Friction::new(0.0).with_combine_rule(CoefficientCombine::Min),
+)]
+pub struct Projectile;
+
+impl Prefab for Projectile {
+    fn reset(entity: &mut EntityWorldMut) {
+        entity.remove::<(
+            Hitbox,
+            AlreadyHit,
+            MultiHit,
+            FriendlyHitbox,
+            EnemyHitbox,
+            CollisionLayers,
+            DecrementDurabilityOnHit,
+            Damage,
+            Pierce,
+            Ricochet,
+            Homing,
+            Lifetime,
+            OnHitEffects,
+            Critical,
+            Faction,
+            Wielder,
+            BitSource,
+        )>();
+    }
+}
+
+/// Launch speed of a weapon's projectiles.
+#[derive(Clone, Copy, Component)]
+pub struct ProjectileSpeed(pub f32);
+
+impl Default for ProjectileSpeed {
+    fn default() -> Self {
+        Self(400.0)
+    }
+}
+
+/// Diameter of a weapon's projectiles.
+#[derive(Clone, Copy, Component)]
+pub struct ProjectileSize(pub f32);
+
+impl Default for ProjectileSize {
+    fn default() -> Self {
+        Self(20.0)
+    }
+}
+
+/// Image used for a weapon's projectiles.
+///
+/// Projectiles are drawn as white squares without one.
+#[derive(Clone, Copy, Component)]
+pub struct ProjectileSprite(pub &'static str);
+
+/// Linear damping applied to a weapon's projectiles.
+#[derive(Default, Clone, Copy, Component)]
+pub struct ProjectileDrag(pub f32);
+
+/// Number of hurtboxes a projectile passes through before it is destroyed.
+///
+/// Placed on a weapon, every projectile it fires will pierce.
+#[derive(Clone, Copy, Component)]
+pub struct Pierce(pub usize);
+
+/// Number of times a projectile bounces off of a [`Layer::Wall`] before it
+/// is destroyed.
+///
+/// Placed on a weapon, every projectile it fires will ricochet.
+#[derive(Clone, Copy, Component)]
+pub struct Ricochet(pub usize);
+
+/// Steers a projectile towards the closest opposing hurtbox.
+///
+/// Placed on a weapon, every projectile it fires will home.
+#[derive(Clone, Copy, Component)]
+pub struct Homing {
+    /// Radians per second.
+    pub turn_rate: f32,
+    pub acquire_radius: f32,
+}
+
+/// Despawns a projectile once the timer finishes.
+///
+/// Placed on a weapon, every projectile it fires will expire.
+#[derive(Clone, Component)]
+pub struct Lifetime(Timer);
+
+impl Lifetime {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+fn homing(
+    time: Res<Time>,
+    mut projectiles: Query<
+        (
+            &mut LinearVelocity,
+            &GlobalTransform,
+            &Homing,
+            Has<FriendlyHitbox>,
+        ),
+        With<Projectile>,
+    >,
+    enemy_hurtboxes: Query<&GlobalTransform, With<EnemyHurtbox>>,
+    friendly_hurtboxes: Query<&GlobalTransform, With<FriendlyHurtbox>>,
+) {
+    for (mut velocity, gt, homing, friendly) in projectiles.iter_mut() {
+        let translation = gt.translation().xy();
+        let radius_squared = homing.acquire_radius * homing.acquire_radius;
+        let targets = if friendly {
+            enemy_hurtboxes.iter()
+        } else {
+            friendly_hurtboxes.iter()
+        };
+        let Some(target) = targets
+            .map(|t| t.translation().xy())
+            .filter(|t| t.distance_squared(translation) <= radius_squared)
+            .min_by(|a, b| {
+                a.distance_squared(translation)
+                    .total_cmp(&b.distance_squared(translation))
+            })
+        else {
+            continue;
+        };
+
+        let desired = (target - translation).normalize_or_zero();
+        if desired == Vec2::ZERO || velocity.0 == Vec2::ZERO {
+            continue;
+        }
+        let max_turn = homing.turn_rate * time.delta_secs();
+        let turn = velocity.0.angle_to(desired).clamp(-max_turn, max_turn);
+        velocity.0 = Vec2::from_angle(turn).rotate(velocity.0);
+    }
+}
+
+fn orient_projectiles(
+    mut projectiles: Query<
+        (&mut Transform, &LinearVelocity),
+        (With<Projectile>, Without<Inactive>),
+    >,
+) {
+    for (mut transform, velocity) in projectiles.iter_mut() {
+        let direction = velocity.0.normalize_or_zero();
+        if direction != Vec2::ZERO {
+            transform.rotation = Quat::from_rotation_z(Vec2::Y.angle_to(direction));
+        }
+    }
+}
+
+fn projectile_lifetime(
+    mut pool: EntityPool,
+    time: Res<Time>,
+    mut lifetimes: Query<(Entity, &mut Lifetime), Without<Weapon>>,
+) {
+    for (entity, mut lifetime) in lifetimes.iter_mut() {
+        lifetime.0.tick(time.delta());
+        if lifetime.0.is_finished() {
+            pool.despawn(entity);
+        }
+    }
+}
+
+fn projectile_wall_impact(
+    start: On<CollisionStart>,
+    mut pool: EntityPool,
+    walls: Query<&CollisionLayers>,
+    mut projectiles: Query<Option<&mut Ricochet>, With<Projectile>>,
+) {
+    if let Ok(layers) = walls.get(start.collider1)
+        && layers.memberships.has_all(Layer::Wall)
+        && let Ok(ricochet) = projectiles.get_mut(start.collider2)
+    {
+        match ricochet {
+            Some(mut ricochet) if ricochet.0 > 0 => ricochet.0 -= 1,
+            _ => pool.despawn(start.collider2),
+        }
+    }
+}
+
+fn despawn_offscreen(
+    mut pool: EntityPool,
+    projectiles: Query<(Entity, &GlobalTransform), (With<Projectile>, Without<Inactive>)>,
+) {
+    let w = WIDTH / 2.0;
+    let h = HEIGHT / 2.0;
+    for (entity, gt) in projectiles.iter() {
+        let translation = gt.translation();
+        if translation.x > w || translation.x < -w || translation.y > h || translation.y < -h {
+            pool.despawn(entity);
+        }
+    }
+}
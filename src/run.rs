@@ -0,0 +1,109 @@
+use crate::{
+    boss::Boss,
+    enemy::Enemy,
+    health::{CurrentHealth, DeathEvent, DeathSystems},
+    player::Player,
+    weapon::HitEvent,
+};
+use bevy::prelude::*;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<RunStats>()
+        .init_resource::<Wallet>()
+        .init_resource::<Depth>()
+        .add_systems(Startup, spawn_wallet_hud)
+        .add_systems(Update, update_wallet_hud)
+        .add_systems(FixedPostUpdate, record_deaths.in_set(DeathSystems::Prepare))
+        .add_observer(record_hits);
+}
+
+/// Statistics collected over the course of a run.
+#[derive(Debug, Default, Resource)]
+pub struct RunStats {
+    pub hits: usize,
+    pub crits: usize,
+    pub damage_dealt: f32,
+    pub damage_taken: f32,
+    pub biggest_hit: f32,
+    pub kills: usize,
+    pub bits_collected: usize,
+    /// Bits taken in by absorbers.
+    pub bits_absorbed: usize,
+    /// Bits that expired or were culled before anything took them in.
+    pub bits_wasted: usize,
+}
+
+/// Kills needed to go one level of [`Depth`] deeper.
+const KILLS_PER_DEPTH: usize = 10;
+
+/// How deep the run has gone, which makes spawned enemies tougher.
+#[derive(Debug, Default, Resource)]
+pub struct Depth(pub usize);
+
+/// Bits collected by the player over the run.
+#[derive(Debug, Default, Resource)]
+pub struct Wallet {
+    pub bits: usize,
+}
+
+fn record_hits(
+    hit: On<HitEvent>,
+    mut stats: ResMut<RunStats>,
+    targets: Query<Has<Player>, With<CurrentHealth>>,
+) {
+    let Ok(is_player) = targets.get(hit.target) else {
+        return;
+    };
+
+    if is_player {
+        stats.damage_taken += hit.damage;
+    } else {
+        stats.hits += 1;
+        stats.crits += hit.crit as usize;
+        stats.damage_dealt += hit.damage;
+        stats.biggest_hit = stats.biggest_hit.max(hit.damage);
+    }
+}
+
+/// Counts kills and reports the run once the player dies.
+fn record_deaths(
+    mut reader: MessageReader<DeathEvent>,
+    mut stats: ResMut<RunStats>,
+    mut depth: ResMut<Depth>,
+    enemies: Query<(), Or<(With<Enemy>, With<Boss>)>>,
+    players: Query<(), With<Player>>,
+) {
+    for event in reader.read() {
+        if enemies.contains(event.0) {
+            stats.kills += 1;
+            if stats.kills.is_multiple_of(KILLS_PER_DEPTH) {
+                depth.0 += 1;
+            }
+        } else if players.contains(event.0) {
+            info!("run ended: {:?}", *stats);
+        }
+    }
+}
+
+#[derive(Component)]
+struct WalletText;
+
+fn spawn_wallet_hud(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Wallet"),
+        WalletText,
+        Text::new("Bits: 0"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(10.0),
+            left: Val::Px(10.0),
+            ..Default::default()
+        },
+    ));
+}
+
+fn update_wallet_hud(wallet: Res<Wallet>, mut text: Single<&mut Text, With<WalletText>>) {
+    if wallet.is_changed() {
+        text.0 = format!("Bits: {}", wallet.bits);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::{
+    enemy::{EnableAttacks, KeepDistance, SteerTarget},
+    faction::FactionSystems,
+    navigation::NavigationSystems,
+    weapon::{self, AttackHandler, Weapon, WeaponPickup, WeaponReach},
+};
+use bevy::{ecs::entity::EntityHashSet, prelude::*};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        FixedPostUpdate,
+        (seek_weapons, pick_up_weapons)
+            .chain()
+            .before(FactionSystems::Target)
+            .before(NavigationSystems::Plan),
+    );
+}
+
+/// Distance from which a [`Scavenger`] notices a [`WeaponPickup`].
+const SCAVENGE_RADIUS: f32 = 300.0;
+
+/// Distance a ranged [`Scavenger`] keeps from its target.
+const RANGED_DISTANCE: f32 = 150.0;
+
+/// An enemy that spawns unarmed and steers toward nearby [`WeaponPickup`]s.
+///
+/// Once armed, it attacks with the weapon, keeping its distance if the weapon is ranged.
+#[derive(Component)]
+pub struct Scavenger;
+
+impl Scavenger {
+    pub const SIZE: f32 = 18.0;
+}
+
+/// Marks an unarmed [`Scavenger`] steering at a pickup.
+///
+/// Its [`SteerTarget`] is left alone by [`FactionSystems::Target`] until it is armed.
+#[derive(Component)]
+pub struct SeekingWeapon;
+
+/// Steers unarmed scavengers at the closest pickup, overriding their hostile target.
+fn seek_weapons(
+    mut commands: Commands,
+    scavengers: Query<
+        (
+            Entity,
+            &GlobalTransform,
+            Option<&SteerTarget>,
+            Option<&Children>,
+            Has<SeekingWeapon>,
+        ),
+        With<Scavenger>,
+    >,
+    weapons: Query<(), With<Weapon>>,
+    pickups: Query<(Entity, &GlobalTransform), With<WeaponPickup>>,
+) {
+    for (entity, transform, steer_target, children, seeking) in scavengers.iter() {
+        let armed = children.is_some_and(|children| weapons.iter_many(children).next().is_some());
+        let translation = transform.translation().xy();
+        let target = pickups
+            .iter()
+            .filter(|_| !armed)
+            .map(|(pickup, transform)| {
+                (
+                    pickup,
+                    transform.translation().xy().distance_squared(translation),
+                )
+            })
+            .filter(|(_, distance)| *distance <= SCAVENGE_RADIUS * SCAVENGE_RADIUS)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(pickup, _)| pickup);
+        // Once armed or out of pickups, the faction picks the target again.
+        let Some(target) = target else {
+            if seeking {
+                commands.entity(entity).remove::<SeekingWeapon>();
+            }
+            continue;
+        };
+        if steer_target.is_none_or(|steer_target| steer_target.0 != target) {
+            commands.entity(entity).insert(SteerTarget(target));
+        }
+        if !seeking {
+            commands.entity(entity).insert(SeekingWeapon);
+        }
+    }
+}
+
+/// Equips pickups within reach of unarmed scavengers through [`weapon::equip`].
+fn pick_up_weapons(
+    mut commands: Commands,
+    scavengers: Query<(Entity, &GlobalTransform, &SteerTarget, Option<&Children>), With<Scavenger>>,
+    weapons: Query<(), With<Weapon>>,
+    pickups: Query<(&GlobalTransform, &WeaponPickup, &AttackHandler)>,
+) {
+    let mut claimed = EntityHashSet::default();
+    for (entity, transform, steer_target, children) in scavengers.iter() {
+        if children.is_some_and(|children| weapons.iter_many(children).next().is_some()) {
+            continue;
+        }
+        let Ok((pickup_transform, pickup, handler)) = pickups.get(steer_target.0) else {
+            continue;
+        };
+        let distance = pickup_transform
+            .translation()
+            .xy()
+            .distance(transform.translation().xy());
+        if distance > pickup.0 || !claimed.insert(steer_target.0) {
+            continue;
+        }
+
+        let ranged = handler.is_ranged();
+        let reach = if ranged { 0.8 } else { 1.2 } * Scavenger::SIZE;
+        weapon::equip(&mut commands, steer_target.0, entity).insert(WeaponReach(reach));
+        let mut entity = commands.entity(entity);
+        entity.insert(EnableAttacks).remove::<SeekingWeapon>();
+        if ranged {
+            entity.insert(KeepDistance(RANGED_DISTANCE));
+        } else {
+            entity.remove::<KeepDistance>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        faction::{self, Faction},
+        health::CurrentHealth,
+    };
+
+    #[test]
+    fn unarmed_scavengers_keep_their_pickup_target() {
+        let mut app = App::new();
+        app.add_plugins((faction::plugin, plugin));
+
+        let world = app.world_mut();
+        let hostile = world
+            .spawn((
+                Faction::Player,
+                CurrentHealth(1.0),
+                GlobalTransform::from_xyz(20.0, 0.0, 0.0),
+            ))
+            .id();
+        let pickup = world
+            .spawn((
+                WeaponPickup::default(),
+                GlobalTransform::from_xyz(200.0, 0.0, 0.0),
+            ))
+            .id();
+        let scavenger = world
+            .spawn((
+                Scavenger,
+                Faction::Scavengers,
+                GlobalTransform::default(),
+                SteerTarget(hostile),
+            ))
+            .id();
+
+        for _ in 0..3 {
+            app.world_mut().run_schedule(FixedPostUpdate);
+            let steer_target = app.world().get::<SteerTarget>(scavenger).unwrap();
+            assert_eq!(steer_target.0, pickup);
+        }
+    }
+}
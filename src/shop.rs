@@ -0,0 +1,247 @@
+use crate::{
+    feedback::popup,
+    health::{CurrentHealth, MaxHealth},
+    player::{
+        Player,
+        input::PickUp,
+        stats::{self, Perk, Perks},
+    },
+    run::Wallet,
+    weapon::{self, MaxDurability, Weapon, WeaponDurability, WeaponPickup, affix::Affixes},
+};
+use bevy::{
+    color::palettes::css::{GOLD, GREEN, RED},
+    prelude::*,
+};
+use bevy_enhanced_input::prelude::*;
+use bevy_rand::{global::GlobalRng, prelude::WyRand};
+use rand::Rng;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(stock_shop).add_observer(buy);
+}
+
+/// Every offer a [`Shop`] can stock, along with its relative chance to be stocked.
+pub const OFFERS: &[(Offer, u32)] = &[
+    (Offer::new(Goods::Weapon(ShopWeapon::Dagger), 10), 4),
+    (Offer::new(Goods::Weapon(ShopWeapon::Broadsword), 20), 3),
+    (Offer::new(Goods::Weapon(ShopWeapon::Axe), 30), 2),
+    (Offer::new(Goods::Weapon(ShopWeapon::Pistol), 20), 3),
+    (Offer::new(Goods::Weapon(ShopWeapon::Shotgun), 35), 2),
+    (Offer::new(Goods::Repair(3), 8), 5),
+    (Offer::new(Goods::Heal(5.0), 10), 5),
+    (Offer::new(Goods::Perk(&stats::VITALITY), 30), 2),
+    (Offer::new(Goods::Perk(&stats::ENDURANCE), 25), 2),
+    (Offer::new(Goods::Perk(&stats::MAGNETISM), 25), 2),
+    (Offer::new(Goods::Perk(&stats::LONG_DASH), 30), 2),
+    (Offer::new(Goods::Perk(&stats::LONG_FINISHER), 30), 2),
+    (Offer::new(Goods::Perk(&stats::BITS_HEAL), 40), 1),
+];
+
+/// Distance from which the player can buy an offer.
+const BUY_RADIUS: f32 = 30.0;
+
+/// Whether an offer at `offer` can be bought by a player at `player`.
+///
+/// Weapons are not picked up while an offer is within reach, since both use
+/// the `PickUp` action.
+pub fn within_reach(offer: &GlobalTransform, player: Vec2) -> bool {
+    offer.translation().xy().distance(player) <= BUY_RADIUS
+}
+
+/// Spacing between offers on a shop's counter.
+const OFFER_SPACING: f32 = 60.0;
+
+/// Stocks `offers` distinct [`OFFERS`] in a row when spawned.
+///
+/// Offers are rolled from the seeded RNG.
+#[derive(Component)]
+#[require(Transform, Visibility)]
+pub struct Shop {
+    pub offers: usize,
+}
+
+impl Default for Shop {
+    fn default() -> Self {
+        Self { offers: 3 }
+    }
+}
+
+/// Goods for sale, bought with the `PickUp` action.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Offer {
+    pub goods: Goods,
+    /// Price in bits.
+    pub price: usize,
+}
+
+impl Offer {
+    pub const fn new(goods: Goods, price: usize) -> Self {
+        Self { goods, price }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Goods {
+    /// Spawns the weapon as a pickup.
+    Weapon(ShopWeapon),
+    /// Restores durability to the held weapon.
+    Repair(usize),
+    Heal(f32),
+    /// A permanent upgrade for the rest of the run.
+    Perk(&'static Perk),
+}
+
+impl Goods {
+    pub fn name(&self) -> String {
+        match self {
+            Self::Weapon(weapon) => format!("{weapon:?}"),
+            Self::Repair(durability) => format!("Repair +{durability}"),
+            Self::Heal(health) => format!("Heal +{health}"),
+            Self::Perk(perk) => perk.name.to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum ShopWeapon {
+    Dagger,
+    Broadsword,
+    Axe,
+    Pistol,
+    Shotgun,
+}
+
+impl ShopWeapon {
+    fn spawn<'a>(self, commands: &'a mut Commands) -> EntityCommands<'a> {
+        match self {
+            Self::Dagger => commands.spawn(weapon::Dagger),
+            Self::Broadsword => commands.spawn(weapon::Broadsword),
+            Self::Axe => commands.spawn(weapon::Axe),
+            Self::Pistol => commands.spawn(weapon::Pistol),
+            Self::Shotgun => commands.spawn(weapon::Shotgun),
+        }
+    }
+}
+
+fn stock_shop(
+    trigger: On<Add, Shop>,
+    mut commands: Commands,
+    shops: Query<&Shop>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) -> Result {
+    let shop = shops.get(trigger.entity)?;
+
+    let mut candidates = OFFERS.to_vec();
+    let count = shop.offers.min(candidates.len());
+    for i in 0..count {
+        let total: u32 = candidates.iter().map(|(_, weight)| weight).sum();
+        let mut selection = rng.random_range(0..total);
+        let index = candidates
+            .iter()
+            .position(|(_, weight)| {
+                let found = selection < *weight;
+                selection = selection.saturating_sub(*weight);
+                found
+            })
+            .unwrap();
+        let (offer, _) = candidates.swap_remove(index);
+
+        let x = (i as f32 - (count - 1) as f32 / 2.0) * OFFER_SPACING;
+        commands.spawn((
+            Name::new("Offer"),
+            offer,
+            ChildOf(trigger.entity),
+            Sprite::from_color(GOLD, Vec2::splat(16.0)),
+            Transform::from_xyz(x, 0.0, 0.0),
+            children![(
+                Text2d::new(format!("{}\n{} bits", offer.goods.name(), offer.price)),
+                TextFont::from_font_size(10.0),
+                Transform::from_xyz(0.0, 25.0, 1.0),
+            )],
+        ));
+    }
+    Ok(())
+}
+
+fn buy(
+    _pick_up: On<Fire<PickUp>>,
+    mut commands: Commands,
+    mut wallet: ResMut<Wallet>,
+    player: Single<
+        (
+            &GlobalTransform,
+            Option<&Children>,
+            Option<(&mut CurrentHealth, &MaxHealth)>,
+            &mut Perks,
+        ),
+        With<Player>,
+    >,
+    offers: Query<(Entity, &Offer, &GlobalTransform)>,
+    mut weapons: Query<(&mut WeaponDurability, &MaxDurability, Option<&Affixes>), With<Weapon>>,
+) {
+    let (player_transform, children, mut health, mut perks) = player.into_inner();
+    let player_translation = player_transform.translation().xy();
+    let Some((entity, offer, offer_transform)) = offers
+        .iter()
+        .filter(|(_, _, transform)| within_reach(transform, player_translation))
+        .min_by(|(_, _, a), (_, _, b)| {
+            let a = a.translation().xy().distance_squared(player_translation);
+            let b = b.translation().xy().distance_squared(player_translation);
+            a.total_cmp(&b)
+        })
+    else {
+        return;
+    };
+
+    let reject = |commands: &mut Commands, reason: &str| {
+        commands.spawn(popup(reason, 12.0, RED.into(), player_translation));
+    };
+    if wallet.bits < offer.price {
+        let missing = offer.price - wallet.bits;
+        reject(&mut commands, &format!("Need {missing} more bits"));
+        return;
+    }
+
+    match offer.goods {
+        Goods::Weapon(weapon) => {
+            weapon
+                .spawn(&mut commands)
+                .insert((WeaponPickup::default(), offer_transform.compute_transform()));
+        }
+        Goods::Repair(durability) => {
+            let mut held = children.map(|children| weapons.iter_many_mut(children));
+            let Some((mut held, max, affixes)) = held.as_mut().and_then(|held| held.fetch_next())
+            else {
+                reject(&mut commands, "Nothing to repair");
+                return;
+            };
+            let max = max.0 + affixes.map_or(0, Affixes::durability);
+            let (WeaponDurability::Fire(current) | WeaponDurability::Hit(current)) = &mut *held;
+            if *current >= max {
+                reject(&mut commands, "Already repaired");
+                return;
+            }
+            *current = (*current + durability).min(max);
+        }
+        Goods::Heal(amount) => match &mut health {
+            Some((current, max)) if current.0 < max.0 => {
+                current.0 = (current.0 + amount).min(max.0);
+            }
+            _ => {
+                reject(&mut commands, "Already healthy");
+                return;
+            }
+        },
+        Goods::Perk(perk) => perks.0.push(perk),
+    }
+
+    wallet.bits -= offer.price;
+    commands.entity(entity).despawn();
+    commands.spawn(popup(
+        format!("Bought {}", offer.goods.name()),
+        12.0,
+        GREEN.into(),
+        player_translation,
+    ));
+}
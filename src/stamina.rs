@@ -0,0 +1,133 @@
+use crate::{
+    block::{Blocked, Blocking},
+    query::AncestorQuery,
+};
+use bevy::{
+    color::palettes::css::{BLACK, GREEN},
+    prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        (regenerate_stamina, spawn_stamina_bars, update_stamina_bars).chain(),
+    )
+    .add_observer(block_stamina);
+}
+
+/// Stamina spent by every hit absorbed while [`Blocking`].
+pub const BLOCKED_HIT_COST: f32 = 15.0;
+
+/// A resource spent by actions such as dashing, blocking and charged attacks.
+///
+/// Spending stamina resets the `regen_delay`, after which `current` refills
+/// at `regen_rate` per second.
+#[derive(Component)]
+pub struct Stamina {
+    pub max: f32,
+    pub current: f32,
+    pub regen_rate: f32,
+    regen_delay: Timer,
+}
+
+impl Default for Stamina {
+    fn default() -> Self {
+        Self::new(100.0, 40.0, 0.75)
+    }
+}
+
+impl Stamina {
+    pub fn new(max: f32, regen_rate: f32, regen_delay: f32) -> Self {
+        Self {
+            max,
+            current: max,
+            regen_rate,
+            regen_delay: Timer::from_seconds(regen_delay, TimerMode::Once),
+        }
+    }
+
+    /// Spends `cost` only if there is enough stamina to pay all of it.
+    pub fn try_spend(&mut self, cost: f32) -> bool {
+        if self.current < cost {
+            return false;
+        }
+        self.drain(cost);
+        true
+    }
+
+    /// Spends as much of `cost` as possible and returns the fraction paid in `0.0..=1.0`.
+    pub fn drain(&mut self, cost: f32) -> f32 {
+        if cost <= 0.0 {
+            return 1.0;
+        }
+        let paid = cost.min(self.current);
+        self.current -= paid;
+        self.regen_delay.reset();
+        paid / cost
+    }
+}
+
+fn regenerate_stamina(time: Res<Time>, mut stamina: Query<&mut Stamina>) {
+    for mut stamina in stamina.iter_mut() {
+        stamina.regen_delay.tick(time.delta());
+        if stamina.regen_delay.is_finished() && stamina.current < stamina.max {
+            let regen = stamina.regen_rate * time.delta_secs();
+            stamina.current = (stamina.current + regen).min(stamina.max);
+        }
+    }
+}
+
+/// Blocked hits drain stamina, and the guard breaks once it runs out.
+fn block_stamina(blocked: On<Blocked>, mut commands: Commands, mut stamina: Query<&mut Stamina>) {
+    if let Ok(mut stamina) = stamina.get_mut(blocked.0)
+        && stamina.drain(BLOCKED_HIT_COST) < 1.0
+    {
+        commands.entity(blocked.0).remove::<Blocking>();
+    }
+}
+
+#[derive(Component)]
+#[relationship_target(relationship = StaminaBarOf, linked_spawn)]
+pub struct StaminaBars(Vec<Entity>);
+
+#[derive(Component)]
+#[relationship(relationship_target = StaminaBars)]
+pub struct StaminaBarOf(Entity);
+
+#[derive(Component)]
+struct StaminaBarFront;
+
+fn spawn_stamina_bars(mut commands: Commands, bars: Query<Entity, Added<Stamina>>) {
+    for entity in bars.iter() {
+        commands.spawn((
+            Name::new("Stamina bar"),
+            StaminaBarOf(entity),
+            Sprite::from_color(BLACK, Vec2::new(50.0, 3.0)),
+            Transform::from_xyz(0.0, 10.0, 1.0),
+            children![(
+                StaminaBarFront,
+                Sprite::from_color(GREEN, Vec2::new(50.0, 3.0)),
+                Transform::from_xyz(0.0, 0.0, 1.0),
+            )],
+        ));
+    }
+}
+
+fn update_stamina_bars(
+    stamina: AncestorQuery<&Stamina, (), StaminaBarOf>,
+    mut bars: Query<(&mut Transform, &ChildOf), With<StaminaBarFront>>,
+    mut back_bars: Query<(Entity, &mut Transform), (Without<StaminaBarFront>, With<StaminaBarOf>)>,
+    global_transforms: AncestorQuery<&GlobalTransform, (), StaminaBarOf>,
+) -> Result {
+    for (entity, mut transform) in back_bars.iter_mut() {
+        let gt = global_transforms.get(entity)?;
+        let newt = gt.compute_transform().translation;
+        transform.translation.x = newt.x;
+        transform.translation.y = newt.y + 10.0;
+    }
+    for (mut transform, child_of) in bars.iter_mut() {
+        let stamina = stamina.get(child_of.0)?;
+        transform.scale.x = stamina.current / stamina.max;
+    }
+    Ok(())
+}
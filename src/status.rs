@@ -0,0 +1,361 @@
+use crate::{bits::BitSource, enemy::FinisherTarget, faction::Charmed, weapon::HitEvent};
+use avian2d::prelude::MaxLinearSpeed;
+use bevy::{
+    color::palettes::css::{CRIMSON, HOT_PINK, LIGHT_CYAN, LIMEGREEN, ORANGE_RED, YELLOW},
+    prelude::*,
+};
+use bevy_rand::{global::GlobalRng, prelude::WyRand};
+use bevy_tween::bevy_time_runner::TimeRunner;
+use rand::Rng;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, (tick_statuses, freeze_tweens, tint_statuses))
+        .add_observer(inflict)
+        .add_observer(expire)
+        .add_observer(thaw);
+}
+
+/// Seconds between damage ticks of damage-over-time effects.
+const TICK_SECS: f32 = 0.5;
+
+/// Fraction of [`MaxLinearSpeed`] kept while [`Frozen`].
+const FREEZE_SLOW: f32 = 0.25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusKind {
+    Bleed,
+    Poison,
+    Burn,
+    Freeze,
+    Stun,
+    /// Flips the afflicted root to the player's [`Faction`](crate::faction::Faction).
+    Charm,
+}
+
+impl StatusKind {
+    /// Inflicting an active effect adds stacks up to this limit.
+    pub fn max_stacks(self) -> usize {
+        match self {
+            Self::Bleed => 5,
+            Self::Poison => 10,
+            Self::Burn | Self::Freeze | Self::Stun | Self::Charm => 1,
+        }
+    }
+
+    /// Whether inflicting an active effect extends its duration.
+    ///
+    /// Poison stacks without refreshing, and stuns can not be chained.
+    pub fn refreshes(self) -> bool {
+        matches!(self, Self::Bleed | Self::Burn | Self::Freeze | Self::Charm)
+    }
+
+    /// Damage per stack every [`TICK_SECS`].
+    pub fn damage(self) -> f32 {
+        match self {
+            Self::Bleed => 0.1,
+            Self::Poison => 0.05,
+            Self::Burn => 0.3,
+            Self::Freeze | Self::Stun | Self::Charm => 0.0,
+        }
+    }
+
+    pub fn tint(self) -> Color {
+        match self {
+            Self::Bleed => CRIMSON.into(),
+            Self::Poison => LIMEGREEN.into(),
+            Self::Burn => ORANGE_RED.into(),
+            Self::Freeze => LIGHT_CYAN.into(),
+            Self::Stun => YELLOW.into(),
+            Self::Charm => HOT_PINK.into(),
+        }
+    }
+}
+
+/// A status effect a weapon may inflict when its attacks land.
+#[derive(Debug, Clone, Copy)]
+pub struct OnHitEffect {
+    pub kind: StatusKind,
+    /// Probability in `0.0..=1.0` that a hit inflicts the effect.
+    pub chance: f32,
+    /// Seconds the effect lasts.
+    pub duration: f32,
+    pub stacks: usize,
+}
+
+impl OnHitEffect {
+    pub const fn new(kind: StatusKind, duration: f32) -> Self {
+        Self {
+            kind,
+            chance: 1.0,
+            duration,
+            stacks: 1,
+        }
+    }
+
+    pub const fn with_chance(mut self, chance: f32) -> Self {
+        self.chance = chance;
+        self
+    }
+
+    pub const fn with_stacks(mut self, stacks: usize) -> Self {
+        self.stacks = stacks;
+        self
+    }
+}
+
+/// Status effects inflicted by a weapon's attacks.
+///
+/// Copied from the weapon onto each attack when it is triggered.
+#[derive(Default, Clone, Component)]
+pub struct OnHitEffects(pub Vec<OnHitEffect>);
+
+impl OnHitEffects {
+    pub fn new(effects: impl IntoIterator<Item = OnHitEffect>) -> Self {
+        Self(effects.into_iter().collect())
+    }
+}
+
+/// Status effects that can not be inflicted on this entity.
+#[derive(Default, Component)]
+pub struct StatusImmunity(pub Vec<StatusKind>);
+
+/// An active status effect, spawned as a child of the root it afflicts.
+#[derive(Component)]
+#[require(Name::new("Status effect"))]
+pub struct StatusEffect {
+    pub kind: StatusKind,
+    pub stacks: usize,
+    duration: Timer,
+    tick: Timer,
+}
+
+impl StatusEffect {
+    fn new(effect: &OnHitEffect) -> Self {
+        Self {
+            kind: effect.kind,
+            stacks: effect.stacks.min(effect.kind.max_stacks()),
+            duration: Timer::from_seconds(effect.duration, TimerMode::Once),
+            tick: Timer::from_seconds(TICK_SECS, TimerMode::Repeating),
+        }
+    }
+
+    fn stack(&mut self, effect: &OnHitEffect) {
+        self.stacks = (self.stacks + effect.stacks).min(self.kind.max_stacks());
+        if self.kind.refreshes() && effect.duration > self.duration.remaining_secs() {
+            self.duration = Timer::from_seconds(effect.duration, TimerMode::Once);
+        }
+    }
+}
+
+/// Attempts to inflict `effect` on the root `target`.
+#[derive(EntityEvent)]
+pub struct InflictStatus {
+    pub target: Entity,
+    pub effect: OnHitEffect,
+}
+
+/// The entity can not attack or steer.
+#[derive(Component)]
+pub struct Stunned;
+
+/// The entity is slowed and its animations are paused.
+#[derive(Component)]
+pub struct Frozen {
+    base_speed: Option<f32>,
+}
+
+impl Frozen {
+    /// Slows `speed`, restoring it instead of the previous speed once thawed.
+    pub fn slow(&mut self, speed: f32) -> MaxLinearSpeed {
+        self.base_speed = Some(speed);
+        MaxLinearSpeed(speed * FREEZE_SLOW)
+    }
+}
+
+/// The untinted sprite color of a root, restored once its status effects are cured.
+///
+/// Roots without one keep the color their sprite had when first afflicted.
+#[derive(Clone, Copy, Component)]
+pub struct BaseColor(pub Color);
+
+/// The sprite color of an afflicted root before it was tinted.
+#[derive(Component)]
+struct StatusTint {
+    base: Color,
+}
+
+fn inflict(
+    inflict: On<InflictStatus>,
+    mut commands: Commands,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+    immunity: Query<&StatusImmunity>,
+    children: Query<&Children>,
+    mut statuses: Query<&mut StatusEffect>,
+    speeds: Query<&MaxLinearSpeed>,
+    sprites: Query<(&Sprite, Option<&BaseColor>, Has<FinisherTarget>)>,
+) {
+    let target = inflict.target;
+    let effect = inflict.effect;
+    if immunity
+        .get(target)
+        .is_ok_and(|immunity| immunity.0.contains(&effect.kind))
+        || !rng.random_bool(effect.chance.clamp(0.0, 1.0) as f64)
+    {
+        return;
+    }
+
+    if let Ok(children) = children.get(target) {
+        let mut iter = statuses.iter_many_mut(children);
+        while let Some(mut status) = iter.fetch_next() {
+            if status.kind == effect.kind {
+                status.stack(&effect);
+                return;
+            }
+        }
+    }
+
+    commands.spawn((StatusEffect::new(&effect), ChildOf(target)));
+    match effect.kind {
+        StatusKind::Stun => {
+            commands.entity(target).insert(Stunned);
+        }
+        StatusKind::Charm => {
+            commands.entity(target).insert(Charmed::default());
+        }
+        StatusKind::Freeze => {
+            let base_speed = speeds.get(target).ok().map(|speed| speed.0);
+            let mut entity = commands.entity(target);
+            entity.insert(Frozen { base_speed });
+            if let Some(speed) = base_speed {
+                entity.insert(MaxLinearSpeed(speed * FREEZE_SLOW));
+            }
+        }
+        _ => {}
+    }
+    if let Ok((sprite, base_color, flashing)) = sprites.get(target) {
+        // A flashing finisher target has lost its own color.
+        let base = base_color
+            .map(|base| base.0)
+            .or((!flashing).then_some(sprite.color));
+        if let Some(base) = base {
+            commands.entity(target).insert_if_new(StatusTint { base });
+        }
+    }
+}
+
+/// Deals damage over time and despawns expired effects.
+fn tick_statuses(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut statuses: Query<(Entity, &mut StatusEffect, &ChildOf)>,
+    transforms: Query<&GlobalTransform>,
+) {
+    for (entity, mut status, child_of) in statuses.iter_mut() {
+        status.duration.tick(time.delta());
+        status.tick.tick(time.delta());
+
+        let damage = status.kind.damage() * status.stacks as f32;
+        if damage > 0.0
+            && status.tick.just_finished()
+            && let Ok(transform) = transforms.get(child_of.parent())
+        {
+            let translation = transform.translation().xy();
+            commands
+                .entity(child_of.parent())
+                .trigger(|target| HitEvent {
+                    target,
+                    attacker: None,
+                    wielder: None,
+                    damage,
+                    knockback: Vec2::ZERO,
+                    bits: 0,
+                    source: BitSource::Wild,
+                    target_translation: translation,
+                    attacker_translation: translation,
+                    crit: false,
+                });
+        }
+
+        if status.duration.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+/// Clears the markers of stuns, freezes and charms that ran out.
+fn expire(
+    trigger: On<Remove, StatusEffect>,
+    mut commands: Commands,
+    statuses: Query<(&StatusEffect, &ChildOf)>,
+) {
+    let Ok((status, child_of)) = statuses.get(trigger.entity) else {
+        return;
+    };
+    match status.kind {
+        StatusKind::Stun => {
+            commands.entity(child_of.parent()).try_remove::<Stunned>();
+        }
+        StatusKind::Freeze => {
+            commands.entity(child_of.parent()).try_remove::<Frozen>();
+        }
+        StatusKind::Charm => {
+            commands.entity(child_of.parent()).try_remove::<Charmed>();
+        }
+        _ => {}
+    }
+}
+
+fn thaw(
+    trigger: On<Remove, Frozen>,
+    mut frozen: Query<(&Frozen, Option<&mut MaxLinearSpeed>)>,
+    children: Query<&Children>,
+    mut runners: Query<&mut TimeRunner>,
+) {
+    let Ok((frozen, speed)) = frozen.get_mut(trigger.entity) else {
+        return;
+    };
+    if let Some(mut speed) = speed
+        && let Some(base) = frozen.base_speed
+    {
+        speed.0 = base;
+    }
+    let mut iter = runners.iter_many_mut(children.iter_descendants(trigger.entity));
+    while let Some(mut runner) = iter.fetch_next() {
+        runner.set_paused(false);
+    }
+}
+
+/// Pauses every animation below a frozen root, including ones started while frozen.
+fn freeze_tweens(
+    frozen: Query<Entity, With<Frozen>>,
+    children: Query<&Children>,
+    mut runners: Query<&mut TimeRunner>,
+) {
+    for root in frozen.iter() {
+        let mut iter = runners.iter_many_mut(children.iter_descendants(root));
+        while let Some(mut runner) = iter.fetch_next() {
+            runner.set_paused(true);
+        }
+    }
+}
+
+/// Tints afflicted roots by their first status effect and restores them once cured.
+fn tint_statuses(
+    mut commands: Commands,
+    mut roots: Query<
+        (Entity, &mut Sprite, &StatusTint, Option<&Children>),
+        Without<FinisherTarget>,
+    >,
+    statuses: Query<&StatusEffect>,
+) {
+    for (entity, mut sprite, tint, children) in roots.iter_mut() {
+        let status = children.and_then(|children| statuses.iter_many(children).next());
+        match status {
+            Some(status) => sprite.color = tint.base.mix(&status.kind.tint(), 0.6),
+            None => {
+                sprite.color = tint.base;
+                commands.entity(entity).remove::<StatusTint>();
+            }
+        }
+    }
+}
@@ -0,0 +1,402 @@
+use super::{Spread, WeaponDurability, WeaponPickup};
+use crate::status::{OnHitEffect, StatusKind};
+use bevy::{
+    color::palettes::css::{DODGER_BLUE, GOLD, MEDIUM_PURPLE},
+    prelude::*,
+};
+use rand::Rng;
+use std::f32::consts::PI;
+
+pub fn plugin(app: &mut App) {
+    app.add_observer(grant_durability)
+        .add_observer(revoke_durability)
+        .add_observer(tint_rarity)
+        .add_observer(show_label)
+        .add_observer(hide_label);
+}
+
+/// Every affix that can be rolled onto a dropped weapon.
+pub const AFFIXES: &[Affix] = &[
+    Affix {
+        name: "Sharp",
+        modifier: Modifier::Damage(0.25),
+        weight: 10,
+        rarity: Rarity::Rare,
+        ranged: false,
+    },
+    Affix {
+        name: "Brutal",
+        modifier: Modifier::Damage(0.5),
+        weight: 3,
+        rarity: Rarity::Epic,
+        ranged: false,
+    },
+    Affix {
+        name: "Sturdy",
+        modifier: Modifier::Durability(1),
+        weight: 10,
+        rarity: Rarity::Rare,
+        ranged: false,
+    },
+    Affix {
+        name: "Unbreakable",
+        modifier: Modifier::Durability(3),
+        weight: 3,
+        rarity: Rarity::Epic,
+        ranged: false,
+    },
+    Affix {
+        name: "Twin",
+        modifier: Modifier::ExtraShots(1),
+        weight: 5,
+        rarity: Rarity::Rare,
+        ranged: true,
+    },
+    Affix {
+        name: "Serrated",
+        modifier: Modifier::Inflict(OnHitEffect::new(StatusKind::Bleed, 3.0).with_chance(0.5)),
+        weight: 6,
+        rarity: Rarity::Rare,
+        ranged: false,
+    },
+    Affix {
+        name: "Searing",
+        modifier: Modifier::Inflict(OnHitEffect::new(StatusKind::Burn, 2.0).with_chance(0.3)),
+        weight: 4,
+        rarity: Rarity::Rare,
+        ranged: false,
+    },
+    Affix {
+        name: "Frigid",
+        modifier: Modifier::Inflict(OnHitEffect::new(StatusKind::Freeze, 1.5).with_chance(0.2)),
+        weight: 3,
+        rarity: Rarity::Epic,
+        ranged: false,
+    },
+    Affix {
+        name: "Beguiling",
+        modifier: Modifier::Inflict(OnHitEffect::new(StatusKind::Charm, 4.0).with_chance(0.15)),
+        weight: 2,
+        rarity: Rarity::Epic,
+        ranged: false,
+    },
+    Affix {
+        name: "Greedy",
+        modifier: Modifier::Bits(3),
+        weight: 8,
+        rarity: Rarity::Rare,
+        ranged: false,
+    },
+];
+
+/// Rarity tier of a weapon, which decides how many affixes it rolls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Component)]
+pub enum Rarity {
+    Common,
+    Rare,
+    Epic,
+    Legendary,
+}
+
+impl Rarity {
+    pub const ALL: [Self; 4] = [Self::Common, Self::Rare, Self::Epic, Self::Legendary];
+
+    /// Relative chance to roll this tier.
+    pub fn weight(self) -> u32 {
+        match self {
+            Self::Common => 60,
+            Self::Rare => 25,
+            Self::Epic => 12,
+            Self::Legendary => 3,
+        }
+    }
+
+    pub fn affixes(self) -> usize {
+        match self {
+            Self::Common => 0,
+            Self::Rare => 1,
+            Self::Epic => 2,
+            Self::Legendary => 3,
+        }
+    }
+
+    pub fn color(self) -> Color {
+        match self {
+            Self::Common => Color::WHITE,
+            Self::Rare => DODGER_BLUE.into(),
+            Self::Epic => MEDIUM_PURPLE.into(),
+            Self::Legendary => GOLD.into(),
+        }
+    }
+}
+
+/// A named modifier layered over a weapon's base components.
+#[derive(Debug)]
+pub struct Affix {
+    pub name: &'static str,
+    pub modifier: Modifier,
+    /// Relative chance to roll this affix.
+    pub weight: u32,
+    /// Minimum [`Rarity`] that can roll this affix.
+    pub rarity: Rarity,
+    /// Only rolled on ranged weapons.
+    pub ranged: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Modifier {
+    /// Increases [`AttackDamage`](super::AttackDamage) by a fraction.
+    Damage(f32),
+    /// Adds to the weapon's [`WeaponDurability`].
+    Durability(usize),
+    /// Adds attacks to the weapon's [`Spread`].
+    ExtraShots(usize),
+    Inflict(OnHitEffect),
+    Bits(usize),
+}
+
+/// Affixes layered over a weapon's base components.
+///
+/// The base components are left untouched. Attacks are built with the
+/// modifiers applied, and the durability they grant is taken back when the
+/// affixes are removed.
+#[derive(Component)]
+pub struct Affixes(pub Vec<&'static Affix>);
+
+impl Affixes {
+    fn modifiers(&self) -> impl Iterator<Item = Modifier> + '_ {
+        self.0.iter().map(|affix| affix.modifier)
+    }
+
+    /// Multiplier on the weapon's [`AttackDamage`](super::AttackDamage).
+    pub fn damage(&self) -> f32 {
+        self.modifiers()
+            .fold(1.0, |damage, modifier| match modifier {
+                Modifier::Damage(fraction) => damage * (1.0 + fraction),
+                _ => damage,
+            })
+    }
+
+    /// Durability added to the weapon's [`WeaponDurability`].
+    pub fn durability(&self) -> usize {
+        self.modifiers()
+            .map(|modifier| match modifier {
+                Modifier::Durability(extra) => extra,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// Bits added to the weapon's [`BitProducer`](crate::bits::BitProducer).
+    pub fn bits(&self) -> usize {
+        self.modifiers()
+            .map(|modifier| match modifier {
+                Modifier::Bits(extra) => extra,
+                _ => 0,
+            })
+            .sum()
+    }
+
+    /// The weapon's `spread` with extra shots added.
+    pub fn spread(&self, mut spread: Spread) -> Spread {
+        for modifier in self.modifiers() {
+            if let Modifier::ExtraShots(extra) = modifier {
+                spread.count += extra;
+                if spread.angle == 0.0 {
+                    spread.angle = PI / 24.0;
+                }
+            }
+        }
+        spread
+    }
+
+    /// Status effects inflicted on top of the weapon's
+    /// [`OnHitEffects`](crate::status::OnHitEffects).
+    pub fn effects(&self) -> impl Iterator<Item = OnHitEffect> + '_ {
+        self.modifiers().filter_map(|modifier| match modifier {
+            Modifier::Inflict(effect) => Some(effect),
+            _ => None,
+        })
+    }
+}
+
+/// Rolls a [`Rarity`] and its [`Affixes`] for a dropped weapon.
+///
+/// Affixes only roll onto weapons they can modify, such as extra shots onto
+/// `ranged` weapons and extra durability onto `durable` ones.
+pub fn roll(rng: &mut impl Rng, ranged: bool, durable: bool) -> (Rarity, Affixes) {
+    let total: u32 = Rarity::ALL.iter().map(|rarity| rarity.weight()).sum();
+    let mut selection = rng.random_range(0..total);
+    let rarity = Rarity::ALL
+        .into_iter()
+        .find(|rarity| {
+            let found = selection < rarity.weight();
+            selection = selection.saturating_sub(rarity.weight());
+            found
+        })
+        .unwrap_or(Rarity::Common);
+
+    let mut candidates = AFFIXES
+        .iter()
+        .filter(|affix| {
+            affix.rarity <= rarity
+                && (ranged || !affix.ranged)
+                && (durable || !matches!(affix.modifier, Modifier::Durability(_)))
+        })
+        .collect::<Vec<_>>();
+    let mut affixes = Vec::with_capacity(rarity.affixes());
+    for _ in 0..rarity.affixes() {
+        let total: u32 = candidates.iter().map(|affix| affix.weight).sum();
+        if total == 0 {
+            break;
+        }
+        let mut selection = rng.random_range(0..total);
+        let index = candidates
+            .iter()
+            .position(|affix| {
+                let found = selection < affix.weight;
+                selection = selection.saturating_sub(affix.weight);
+                found
+            })
+            .unwrap();
+        affixes.push(candidates.swap_remove(index));
+    }
+
+    (rarity, Affixes(affixes))
+}
+
+/// Grants the durability of newly inserted affixes.
+fn grant_durability(
+    trigger: On<Insert, Affixes>,
+    mut weapons: Query<(&Affixes, &mut WeaponDurability)>,
+) {
+    if let Ok((affixes, mut durability)) = weapons.get_mut(trigger.entity) {
+        let (WeaponDurability::Fire(durability) | WeaponDurability::Hit(durability)) =
+            &mut *durability;
+        *durability += affixes.durability();
+    }
+}
+
+/// Takes back the durability granted by affixes that are replaced or removed.
+fn revoke_durability(
+    trigger: On<Replace, Affixes>,
+    mut weapons: Query<(&Affixes, &mut WeaponDurability)>,
+) {
+    if let Ok((affixes, mut durability)) = weapons.get_mut(trigger.entity) {
+        let (WeaponDurability::Fire(durability) | WeaponDurability::Hit(durability)) =
+            &mut *durability;
+        *durability = durability.saturating_sub(affixes.durability());
+    }
+}
+
+fn tint_rarity(trigger: On<Add, Rarity>, mut weapons: Query<(&Rarity, &mut Sprite)>) {
+    if let Ok((rarity, mut sprite)) = weapons.get_mut(trigger.entity) {
+        sprite.color = rarity.color();
+    }
+}
+
+/// Names the rarity and affixes of a weapon lying on the floor.
+#[derive(Component)]
+struct AffixLabel;
+
+fn show_label(
+    trigger: On<Add, WeaponPickup>,
+    mut commands: Commands,
+    weapons: Query<(&Rarity, &Affixes, &Transform)>,
+) {
+    let Ok((rarity, affixes, transform)) = weapons.get(trigger.entity) else {
+        return;
+    };
+    if affixes.0.is_empty() {
+        return;
+    }
+
+    let mut text = format!("{rarity:?}");
+    for affix in affixes.0.iter() {
+        text.push('\n');
+        text.push_str(affix.name);
+    }
+    // Undo the weapon's rotation so the label stays upright above it.
+    let rotation = transform.rotation.inverse();
+    commands.spawn((
+        Name::new("Affix label"),
+        AffixLabel,
+        ChildOf(trigger.entity),
+        Text2d::new(text),
+        TextFont::from_font_size(10.0),
+        TextColor(rarity.color()),
+        Transform::from_translation(rotation * Vec3::new(0.0, 30.0, 5.0)).with_rotation(rotation),
+    ));
+}
+
+fn hide_label(
+    trigger: On<Remove, WeaponPickup>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    labels: Query<Entity, With<AffixLabel>>,
+) {
+    if let Ok(children) = children.get(trigger.entity) {
+        for label in labels.iter_many(children) {
+            commands.entity(label).despawn();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_rand::prelude::WyRand;
+    use rand::SeedableRng;
+
+    fn affixes(names: &[&str]) -> Affixes {
+        Affixes(
+            AFFIXES
+                .iter()
+                .filter(|affix| names.contains(&affix.name))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn roll_only_what_the_weapon_can_use() {
+        let mut rng = WyRand::seed_from_u64(0);
+        for _ in 0..1000 {
+            let (rarity, affixes) = roll(&mut rng, false, false);
+            assert!(affixes.0.len() <= rarity.affixes());
+            for (i, affix) in affixes.0.iter().enumerate() {
+                assert!(affix.rarity <= rarity);
+                assert!(!affix.ranged);
+                assert!(!matches!(affix.modifier, Modifier::Durability(_)));
+                assert!(affixes.0[..i].iter().all(|other| other.name != affix.name));
+            }
+        }
+    }
+
+    #[test]
+    fn roll_is_seeded() {
+        let rolls = |seed| {
+            let mut rng = WyRand::seed_from_u64(seed);
+            (0..100)
+                .map(|_| {
+                    let (rarity, affixes) = roll(&mut rng, true, true);
+                    let names = affixes.0.iter().map(|affix| affix.name).collect::<Vec<_>>();
+                    (rarity, names)
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(7), rolls(7));
+    }
+
+    #[test]
+    fn affixes_layer_over_base_values() {
+        let affixes = affixes(&["Sharp", "Brutal", "Sturdy", "Twin", "Greedy", "Serrated"]);
+        assert!((affixes.damage() - 1.25 * 1.5).abs() < 1e-6);
+        assert_eq!(affixes.durability(), 1);
+        assert_eq!(affixes.bits(), 3);
+        assert_eq!(affixes.effects().count(), 1);
+
+        let spread = affixes.spread(Spread::default());
+        assert_eq!(spread.count, 2);
+        assert!(spread.angle > 0.0);
+    }
+}
@@ -0,0 +1,113 @@
+use super::{AttackHandler, Weapon, affix::Rarity};
+use bevy::{
+    color::palettes::css::ORANGE,
+    ecs::{lifecycle::HookContext, world::DeferredWorld},
+    prelude::*,
+};
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, charge)
+        .add_observer(reset_charge_feedback);
+}
+
+/// A stronger attack fired by holding and releasing the attack action.
+///
+/// Releasing before `min_charge` falls back to the weapon's normal attack.
+#[derive(Component)]
+#[component(on_insert = Self::insert)]
+pub struct ChargeAttack {
+    /// Seconds to reach a full charge.
+    pub charge_time: f32,
+    /// Fraction of a full charge needed to fire the charged attack.
+    pub min_charge: f32,
+    /// Damage multiplier at full charge.
+    pub damage: f32,
+    /// Knockback multiplier at full charge.
+    pub knockback: f32,
+    /// Bit multiplier at full charge.
+    pub bits: f32,
+    pub(super) handler: AttackHandler,
+}
+
+impl ChargeAttack {
+    pub fn new(handler: AttackHandler) -> Self {
+        Self {
+            charge_time: 1.0,
+            min_charge: 0.5,
+            damage: 2.0,
+            knockback: 2.0,
+            bits: 2.0,
+            handler,
+        }
+    }
+
+    pub fn with_charge_time(mut self, charge_time: f32) -> Self {
+        self.charge_time = charge_time;
+        self
+    }
+
+    pub fn with_min_charge(mut self, min_charge: f32) -> Self {
+        self.min_charge = min_charge;
+        self
+    }
+
+    pub fn with_scaling(mut self, damage: f32, knockback: f32, bits: f32) -> Self {
+        self.damage = damage;
+        self.knockback = knockback;
+        self.bits = bits;
+        self
+    }
+
+    /// Interpolates a full charge `multiplier` by `charge`.
+    pub fn scale(multiplier: f32, charge: f32) -> f32 {
+        1.0 + (multiplier - 1.0) * charge
+    }
+
+    fn insert(mut world: DeferredWorld, ctx: HookContext) {
+        world.commands().queue(move |world: &mut World| {
+            let mut charge = world.get_mut::<Self>(ctx.entity).unwrap();
+            if let Some(command) = charge.handler.1.take() {
+                command(world);
+            }
+        });
+    }
+}
+
+/// Seconds that the attack action has been held for a [`ChargeAttack`].
+#[derive(Default, Component)]
+pub struct Charging(pub f32);
+
+impl Charging {
+    /// Fraction of a full charge in `0.0..=1.0`.
+    pub fn charge(&self, attack: &ChargeAttack) -> f32 {
+        (self.0 / attack.charge_time.max(f32::EPSILON)).min(1.0)
+    }
+}
+
+fn charge(
+    time: Res<Time>,
+    mut weapons: Query<(&mut Charging, &ChargeAttack, Option<&mut Sprite>), With<Weapon>>,
+) {
+    for (mut charging, attack, sprite) in weapons.iter_mut() {
+        charging.0 += time.delta_secs();
+        if let Some(mut sprite) = sprite {
+            let charge = charging.charge(attack);
+            sprite.color = if charge >= 1.0 {
+                ORANGE.into()
+            } else if charge >= attack.min_charge {
+                Color::WHITE.mix(&ORANGE.into(), charge)
+            } else {
+                Color::WHITE
+            };
+        }
+    }
+}
+
+fn reset_charge_feedback(
+    trigger: On<Remove, Charging>,
+    mut sprites: Query<(&mut Sprite, Option<&Rarity>)>,
+) {
+    if let Ok((mut sprite, rarity)) = sprites.get_mut(trigger.entity) {
+        sprite.color = rarity.map_or(Color::WHITE, |rarity| rarity.color());
+    }
+}
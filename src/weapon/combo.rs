@@ -0,0 +1,97 @@
+use super::{HitEvent, Weapon, melee::MeleeArc};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, combo_window)
+        .add_observer(reset_combo_on_hit);
+}
+
+/// A sequence of melee attacks performed by repeatedly triggering a weapon.
+///
+/// Triggering the weapon within `window` seconds of a step's
+/// [`ComboStep::duration`] continues the chain, otherwise it starts over.
+#[derive(Component)]
+pub struct Combo {
+    pub steps: Vec<ComboStep>,
+    pub window: f32,
+}
+
+impl Combo {
+    pub fn new(window: f32, steps: impl IntoIterator<Item = ComboStep>) -> Self {
+        Self {
+            steps: steps.into_iter().collect(),
+            window,
+        }
+    }
+
+    /// Advances the chain stored in `state` and returns the next step index.
+    pub(super) fn advance(
+        &self,
+        commands: &mut Commands,
+        weapon: Entity,
+        state: Option<&ComboState>,
+    ) -> Option<usize> {
+        if self.steps.is_empty() {
+            return None;
+        }
+
+        let step = match state {
+            Some(state) if !state.window.is_finished() => (state.step + 1) % self.steps.len(),
+            _ => 0,
+        };
+        let window = self.steps[step].duration + self.window;
+        commands.entity(weapon).insert(ComboState {
+            step,
+            window: Timer::from_seconds(window, TimerMode::Once),
+        });
+        Some(step)
+    }
+}
+
+/// A single attack in a [`Combo`].
+pub struct ComboStep {
+    pub collider: Collider,
+    /// Multiplies the weapon's [`AttackDamage`](super::AttackDamage).
+    pub damage: f32,
+    /// Seconds the hitbox is active.
+    pub duration: f32,
+    /// Distance the wielder moves forward over the step.
+    pub lunge: f32,
+    /// Falls back to the weapon's [`MeleeArc`].
+    pub arc: Option<MeleeArc>,
+}
+
+/// The last [`ComboStep`] performed and the window to continue the chain.
+#[derive(Component)]
+pub struct ComboState {
+    pub step: usize,
+    window: Timer,
+}
+
+fn combo_window(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut combos: Query<(Entity, &mut ComboState)>,
+) {
+    for (entity, mut state) in combos.iter_mut() {
+        state.window.tick(time.delta());
+        if state.window.is_finished() {
+            commands.entity(entity).remove::<ComboState>();
+        }
+    }
+}
+
+/// Getting hit breaks the combo of the target's weapon.
+fn reset_combo_on_hit(
+    hit: On<HitEvent>,
+    mut commands: Commands,
+    children: Query<&Children>,
+    combos: Query<Entity, (With<Weapon>, With<ComboState>)>,
+) {
+    if let Ok(children) = children.get(hit.target) {
+        for weapon in combos.iter_many(children) {
+            commands.entity(weapon).remove::<ComboState>();
+        }
+    }
+}
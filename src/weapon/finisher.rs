@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use bevy_tween::prelude::EaseKind;
+
+/// How the wielder finishes a [`FinisherTarget`](crate::enemy::FinisherTarget).
+///
+/// Wielders without a weapon, or whose weapon has no finisher, use the default.
+#[derive(Debug, Clone, Copy, Component)]
+pub struct Finisher {
+    pub animation: FinisherAnimation,
+    /// Seconds the animation takes.
+    pub duration: f32,
+    /// Strikes dealt evenly over the animation, the last of which lands the finisher.
+    pub hits: usize,
+    /// Bits released when the finisher lands.
+    pub bits: usize,
+    /// Damage dealt to hostile roots around the target when the finisher lands.
+    pub area: Option<FinisherArea>,
+    /// Slows time and zooms the camera in for the duration.
+    pub cinematic: bool,
+}
+
+impl Default for Finisher {
+    fn default() -> Self {
+        Self::new(FinisherAnimation::Dash)
+    }
+}
+
+impl Finisher {
+    pub const fn new(animation: FinisherAnimation) -> Self {
+        Self {
+            animation,
+            duration: 0.1,
+            hits: 1,
+            bits: 15,
+            area: None,
+            cinematic: false,
+        }
+    }
+
+    pub const fn with_duration(mut self, duration: f32) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Sets the strikes dealt, of which there is always at least one.
+    pub const fn with_hits(mut self, hits: usize) -> Self {
+        self.hits = if hits == 0 { 1 } else { hits };
+        self
+    }
+
+    pub const fn with_bits(mut self, bits: usize) -> Self {
+        self.bits = bits;
+        self
+    }
+
+    pub const fn with_area(mut self, radius: f32, damage: f32) -> Self {
+        self.area = Some(FinisherArea { radius, damage });
+        self
+    }
+
+    pub const fn cinematic(mut self) -> Self {
+        self.cinematic = true;
+        self
+    }
+}
+
+/// The movement of the wielder during a [`Finisher`].
+#[derive(Debug, Clone, Copy)]
+pub enum FinisherAnimation {
+    /// Dashes onto the target.
+    Dash,
+    /// Dashes through the target, coming to a stop `overshoot` past it.
+    Pierce { overshoot: f32 },
+    /// Winds up slowly before crashing down on the target.
+    Slam,
+}
+
+impl FinisherAnimation {
+    pub fn ease(self) -> EaseKind {
+        match self {
+            Self::Dash => EaseKind::QuarticOut,
+            Self::Pierce { .. } => EaseKind::CubicOut,
+            Self::Slam => EaseKind::QuarticIn,
+        }
+    }
+
+    /// Where the wielder ends up when finishing a target at `target` from `start`.
+    pub fn end(self, start: Vec2, target: Vec2) -> Vec2 {
+        match self {
+            Self::Dash | Self::Slam => target,
+            Self::Pierce { overshoot } => target + (target - start).normalize_or_zero() * overshoot,
+        }
+    }
+}
+
+/// Damage dealt around the target of a [`Finisher`] as it lands.
+///
+/// Like the finisher itself, these hits land on roots directly rather than
+/// through hurtboxes, so they ignore [`Armor`](crate::health::Armor), blocks,
+/// parries and [`WeakPoint`](crate::health::WeakPoint)s.
+#[derive(Debug, Clone, Copy)]
+pub struct FinisherArea {
+    pub radius: f32,
+    pub damage: f32,
+}
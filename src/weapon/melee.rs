@@ -0,0 +1,185 @@
+use super::{AttackDuration, TriggerWeaponData, WeaponReach, combo::Combo};
+use avian2d::prelude::*;
+use bevy::prelude::*;
+use bevy_tween::{
+    BevyTweenRegisterSystems,
+    bevy_time_runner::TimeRunnerEnded,
+    component_tween_system,
+    interpolate::translation,
+    prelude::{AnimationBuilderExt, EaseKind, Interpolator},
+    tween::IntoTarget,
+};
+use std::f32::consts::TAU;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, end_swing)
+        .add_tween_systems(component_tween_system::<SwingTween>());
+}
+
+/// Sweeps a melee weapon and its hitbox through an arc around the wielder.
+///
+/// Angles are in radians relative to the wielder's facing, positive is
+/// counter-clockwise. The sweep lasts for the weapon's [`AttackDuration`].
+#[derive(Clone, Copy, Component)]
+pub struct MeleeArc {
+    pub start_angle: f32,
+    pub end_angle: f32,
+    pub radius: f32,
+    pub ease: EaseKind,
+}
+
+#[derive(Component)]
+pub struct Melee;
+
+/// The animation sweeping a weapon through its [`MeleeArc`].
+#[derive(Component)]
+struct Swing(Entity);
+
+/// The animation moving a wielder forward during a [`ComboStep`](super::combo::ComboStep).
+#[derive(Component)]
+struct Lunge;
+
+/// Performs the weapon's current [`ComboStep`](super::combo::ComboStep) if it has a [`Combo`].
+pub fn default_melee_handler(
+    data: In<TriggerWeaponData>,
+    mut commands: Commands,
+    melee_weapons: Query<(
+        &AttackDuration,
+        &Collider,
+        Option<&MeleeArc>,
+        Option<&Combo>,
+        &ChildOf,
+    )>,
+    wielders: Query<&Transform>,
+) -> Result {
+    let (duration, collider, arc, combo, wielder) = melee_weapons.get(data.weapon)?;
+    let step = combo
+        .zip(data.combo_step)
+        .map(|(combo, step)| &combo.steps[step]);
+    let (duration, collider, arc) = match step {
+        Some(step) => (
+            AttackDuration::from_seconds(step.duration),
+            &step.collider,
+            step.arc.as_ref().or(arc),
+        ),
+        None => (duration.clone(), collider, arc),
+    };
+
+    if let Some(step) = step
+        && step.lunge != 0.0
+        && let Ok(transform) = wielders.get(wielder.parent())
+    {
+        let start = transform.translation;
+        let end = start + (data.attack_vector * step.lunge).extend(0.0);
+        let animation = commands
+            .animation()
+            .insert_tween_here(
+                duration.0.duration(),
+                EaseKind::QuadraticOut,
+                wielder.parent().into_target().with(translation(start, end)),
+            )
+            .insert(Lunge)
+            .id();
+        commands.entity(wielder.parent()).add_child(animation);
+    }
+
+    commands.entity(data.attack).insert((
+        ChildOf(data.weapon),
+        duration.clone(),
+        collider.clone(),
+        Melee,
+    ));
+
+    if let Some(arc) = arc {
+        let animation = commands
+            .animation()
+            .insert_tween_here(
+                duration.0.duration(),
+                arc.ease,
+                data.weapon.into_target().with(swing(*arc)),
+            )
+            .insert(Swing(data.weapon))
+            .id();
+        commands.entity(data.weapon).add_child(animation);
+    }
+    Ok(())
+}
+
+/// Sweeps the weapon through a full circle around the wielder.
+///
+/// Takes twice as long as the weapon's normal swing.
+pub fn spin_melee_handler(
+    data: In<TriggerWeaponData>,
+    mut commands: Commands,
+    melee_weapons: Query<(&AttackDuration, &Collider, &WeaponReach, Option<&MeleeArc>)>,
+) -> Result {
+    let (duration, collider, reach, arc) = melee_weapons.get(data.weapon)?;
+    let duration = AttackDuration::from_seconds(duration.0.duration().as_secs_f32() * 2.0);
+    let arc = MeleeArc {
+        start_angle: 0.0,
+        end_angle: -TAU,
+        radius: arc.map(|arc| arc.radius).unwrap_or(reach.0),
+        ease: arc.map(|arc| arc.ease).unwrap_or(EaseKind::Linear),
+    };
+
+    let animation = commands
+        .animation()
+        .insert_tween_here(
+            duration.0.duration(),
+            arc.ease,
+            data.weapon.into_target().with(swing(arc)),
+        )
+        .insert(Swing(data.weapon))
+        .id();
+    commands.entity(data.weapon).add_child(animation);
+    commands
+        .entity(data.attack)
+        .insert((ChildOf(data.weapon), duration, collider.clone(), Melee));
+    Ok(())
+}
+
+fn end_swing(
+    mut commands: Commands,
+    swings: Query<&Swing>,
+    lunges: Query<(), With<Lunge>>,
+    mut ended: MessageReader<TimeRunnerEnded>,
+    mut reaches: Query<&mut WeaponReach>,
+) {
+    for ended in ended.read() {
+        if ended.is_completed() && lunges.contains(ended.entity) {
+            commands.entity(ended.entity).despawn();
+        } else if ended.is_completed()
+            && let Ok(swing) = swings.get(ended.entity)
+        {
+            commands.entity(ended.entity).despawn();
+            // Return the weapon to its resting position.
+            if let Ok(mut reach) = reaches.get_mut(swing.0) {
+                reach.set_changed();
+            }
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct SwingTween {
+    arc: MeleeArc,
+}
+
+pub fn swing(arc: MeleeArc) -> SwingTween {
+    SwingTween { arc }
+}
+
+impl Interpolator for SwingTween {
+    type Item = Transform;
+    fn interpolate(
+        &self,
+        item: &mut Self::Item,
+        value: bevy_tween::interpolate::CurrentValue,
+        _: bevy_tween::interpolate::PreviousValue,
+    ) {
+        let angle = self.arc.start_angle + (self.arc.end_angle - self.arc.start_angle) * value;
+        let offset = Vec2::from_angle(angle).rotate(Vec2::Y) * self.arc.radius;
+        item.translation = offset.extend(item.translation.z);
+        item.rotation = Quat::from_rotation_z(angle);
+    }
+}
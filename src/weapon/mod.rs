@@ -0,0 +1,1424 @@
+use avian2d::prelude::*;
+use bevy::{
+    ecs::{
+        entity::EntityHashMap,
+        lifecycle::HookContext,
+        system::{SystemId, SystemParam},
+        world::DeferredWorld,
+    },
+    platform::collections::HashMap,
+    prelude::*,
+};
+
+use crate::{
+    Layer,
+    bits::{BitProducer, BitSource},
+    block::{Block, Blocked, Blocking, Guard, Parried},
+    faction::{Faction, FactionRelations},
+    health::{Armor, EnemyHitbox, FriendlyHitbox, Hitbox, Hurtbox, WeakPoint},
+    physics::acceleration,
+    pool::EntityPool,
+    projectile::{
+        Homing, Lifetime, Pierce, Projectile, ProjectileDrag, ProjectileSize, ProjectileSpeed,
+        ProjectileSprite, Ricochet,
+    },
+    query::AncestorQuery,
+    status::{InflictStatus, OnHitEffect, OnHitEffects, StatusKind},
+};
+use affix::Affixes;
+use bevy_rand::{global::GlobalRng, prelude::WyRand};
+use bevy_tween::{
+    combinator::tween,
+    prelude::{AnimationBuilderExt, EaseKind},
+    tween::IntoTarget,
+};
+use charge::ChargeAttack;
+use combo::{Combo, ComboState, ComboStep};
+use finisher::{Finisher, FinisherAnimation};
+use melee::MeleeArc;
+use rand::Rng;
+use std::{any::TypeId, f32::consts::PI, time::Duration};
+
+pub mod affix;
+pub mod charge;
+pub mod combo;
+pub mod finisher;
+pub mod melee;
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((melee::plugin, charge::plugin, combo::plugin, affix::plugin))
+        .init_resource::<AttackHandlerRegistry>()
+        .add_systems(
+            Update,
+            (
+                weapon_sprite,
+                weapon_reach,
+                attack_duration,
+                attack_cooldown,
+                reload,
+                (finish_throw, remove_weapon_rigidbody).chain(),
+            ),
+        )
+        .add_observer(record_max_durability)
+        .add_observer(propogate_trigger_weapon)
+        .add_observer(trigger_weapon)
+        .add_observer(propogate_reload_weapon)
+        .add_observer(reload_weapon)
+        .add_systems(FixedPostUpdate, multi_hit.in_set(PhysicsSystems::Last))
+        .add_observer(handle_attack)
+        .add_observer(hit_event);
+}
+
+// WEAPONS
+
+// GUNS
+
+#[derive(Component)]
+#[require(
+    Weapon,
+    Damage(1.0),
+    WeaponReach(15.0),
+    Magazine::new(6),
+    ReserveAmmo(24),
+    ReloadDuration::from_seconds(1.0),
+    AttackDamage(Damage(1.0)),
+    AttackHandler::bullet(),
+    AttackCooldown::from_seconds(0.2),
+    CritChance(0.05),
+    BitSource::Ranged,
+    PooledAttacks,
+    ProjectileSpeed(400.0),
+    ProjectileSize(20.0),
+    Collider::rectangle(20.0, 20.0),
+    Sprite::from_color(Color::WHITE, Vec2::splat(20.0)),
+    Name::new("Pistol")
+)]
+pub struct Pistol;
+
+#[derive(Component)]
+#[require(
+    Weapon,
+    Damage(0.5),
+    WeaponReach(15.0),
+    WeaponKnockback(300.0),
+    Magazine::new(2),
+    ReserveAmmo(12),
+    ReloadDuration::from_seconds(1.5),
+    AttackDamage(Damage(0.5)),
+    AttackHandler::bullet(),
+    AttackCooldown::from_seconds(0.6),
+    BitSource::Ranged,
+    PooledAttacks,
+    ProjectileSpeed(600.0),
+    ProjectileSize(8.0),
+    ProjectileDrag(2.0),
+    Lifetime::from_seconds(0.6),
+    Spread { count: 5, angle: PI / 6.0 },
+    Collider::rectangle(30.0, 15.0),
+    Sprite::from_color(Color::WHITE, Vec2::new(15.0, 30.0)),
+    Name::new("Shotgun")
+)]
+pub struct Shotgun;
+
+// MELEE
+
+#[derive(Component)]
+#[require(
+    Weapon,
+    Damage(1.0),
+    BitProducer(3),
+    BitSource::Blade,
+    WeaponReach(15.0),
+    WeaponDurability::Hit(3),
+    AttackHandler::melee(),
+    AttackDamage(Damage(1.0)),
+    AttackDuration::from_seconds(0.1),
+    AttackCooldown::from_seconds(0.2),
+    MeleeArc {
+        start_angle: PI / 6.0,
+        end_angle: -PI / 6.0,
+        radius: 15.0,
+        ease: EaseKind::QuadraticOut,
+    },
+    Combo = Self::combo(),
+    Finisher = Finisher::new(FinisherAnimation::Dash)
+        .with_duration(0.3)
+        .with_hits(3)
+        .with_bits(12),
+    CritChance(0.15),
+    OnHitEffects = OnHitEffects::new([OnHitEffect::new(StatusKind::Bleed, 3.0).with_chance(0.35)]),
+    Collider::rectangle(50.0, 20.0),
+    WeaponSprite("weapons/1.png"),
+    Name::new("Dagger")
+)]
+pub struct Dagger;
+
+impl Dagger {
+    fn combo() -> Combo {
+        Combo::new(
+            0.25,
+            [
+                ComboStep {
+                    collider: Collider::rectangle(50.0, 20.0),
+                    damage: 1.0,
+                    duration: 0.1,
+                    lunge: 5.0,
+                    arc: None,
+                },
+                ComboStep {
+                    collider: Collider::rectangle(50.0, 20.0),
+                    damage: 1.0,
+                    duration: 0.1,
+                    lunge: 5.0,
+                    arc: Some(MeleeArc {
+                        start_angle: -PI / 6.0,
+                        end_angle: PI / 6.0,
+                        radius: 15.0,
+                        ease: EaseKind::QuadraticOut,
+                    }),
+                },
+                ComboStep {
+                    collider: Collider::rectangle(20.0, 60.0),
+                    damage: 2.0,
+                    duration: 0.15,
+                    lunge: 20.0,
+                    arc: Some(MeleeArc {
+                        start_angle: 0.0,
+                        end_angle: 0.0,
+                        radius: 25.0,
+                        ease: EaseKind::QuarticOut,
+                    }),
+                },
+            ],
+        )
+    }
+}
+
+#[derive(Component)]
+#[require(
+    Weapon,
+    Damage(1.5),
+    BitProducer(5),
+    BitSource::Heavy,
+    WeaponReach(25.0),
+    WeaponDurability::Hit(3),
+    AttackHandler::melee(),
+    AttackDamage(Damage(1.5)),
+    AttackDuration::from_seconds(0.2),
+    AttackCooldown::from_seconds(0.4),
+    MeleeArc {
+        start_angle: PI / 2.0,
+        end_angle: -PI / 2.0,
+        radius: 25.0,
+        ease: EaseKind::CubicOut,
+    },
+    ChargeAttack::new(AttackHandler::spin()),
+    Finisher = Finisher::new(FinisherAnimation::Pierce { overshoot: 40.0 })
+        .with_duration(0.2)
+        .with_area(60.0, 1.0),
+    Collider::rectangle(35.0, 55.0),
+    WeaponSprite("weapons/4.png"),
+    Name::new("Broadsword")
+)]
+pub struct Broadsword;
+
+#[derive(Component)]
+#[require(
+    Weapon,
+    Damage(2.5),
+    BitProducer(10),
+    BitSource::Heavy,
+    WeaponReach(30.0),
+    WeaponDurability::Hit(3),
+    AttackHandler::melee(),
+    AttackDamage(Damage(2.5)),
+    AttackDuration::from_seconds(0.3),
+    AttackCooldown::from_seconds(1.0),
+    MeleeArc {
+        start_angle: 2.0 * PI / 3.0,
+        end_angle: -2.0 * PI / 3.0,
+        radius: 30.0,
+        ease: EaseKind::QuarticIn,
+    },
+    ChargeAttack::new(AttackHandler::spin())
+        .with_charge_time(1.5)
+        .with_scaling(2.5, 3.0, 2.0),
+    DamageVariance(0.2),
+    CritMultiplier(3.0),
+    OnHitEffects = OnHitEffects::new([OnHitEffect::new(StatusKind::Stun, 1.0).with_chance(0.25)]),
+    Finisher = Finisher::new(FinisherAnimation::Slam)
+        .with_duration(0.5)
+        .with_bits(25)
+        .with_area(90.0, 2.0)
+        .cinematic(),
+    Collider::rectangle(60.0, 60.0),
+    WeaponSprite("weapons/7.png"),
+    Name::new("Axe")
+)]
+pub struct Axe;
+
+// COMPONENTS AND SYSTEMS
+
+#[derive(Default, Component)]
+#[require(
+    Sensor,
+    Transform,
+    BitProducer,
+    BitSource,
+    WeaponKnockback,
+    LockedAxes::ROTATION_LOCKED,
+    // Disable the collider _ON_ the weapon. The weapon's collider should only
+    // be enabled when it is thrown by the player.
+    ColliderDisabled,
+)]
+#[cfg_attr(feature = "debug", require(DebugRender::none()))]
+pub struct Weapon;
+
+#[derive(Clone, Copy, Component)]
+pub struct Damage(pub f32);
+
+/// Probability in `0.0..=1.0` that an attack is a critical hit.
+///
+/// The chances of the weapon and its wielder add together.
+#[derive(Clone, Copy, Component)]
+pub struct CritChance(pub f32);
+
+/// Damage multiplier of critical hits.
+///
+/// The weapon's multiplier takes precedence over its wielder's,
+/// otherwise [`CritMultiplier::DEFAULT`] is used.
+#[derive(Clone, Copy, Component)]
+pub struct CritMultiplier(pub f32);
+
+impl CritMultiplier {
+    pub const DEFAULT: Self = Self(2.0);
+}
+
+/// Scales the damage of each attack by a random factor in `1.0 ± variance`.
+#[derive(Clone, Copy, Component)]
+pub struct DamageVariance(pub f32);
+
+/// The attack rolled a critical hit.
+///
+/// Critical hits deal [`CritMultiplier`] damage, and boost the knockback and
+/// bits of the [`HitEvent`].
+#[derive(Component)]
+pub struct Critical;
+
+/// Knockback multiplier of [`Critical`] hits.
+const CRIT_KNOCKBACK: f32 = 1.5;
+
+/// Bit multiplier of [`Critical`] hits.
+const CRIT_BITS: usize = 2;
+
+/// Determines if [`WeaponDurability`] should be decremented.
+///
+/// This marker can live anywhere above the weapon.
+#[derive(Default, Component)]
+pub struct ApplyWeaponDurability;
+
+/// Number of hits that a weapon can susatin before shattering.
+#[derive(Component)]
+pub enum WeaponDurability {
+    Fire(usize),
+    Hit(usize),
+}
+
+impl WeaponDurability {
+    /// Hits or shots left before the weapon shatters.
+    pub fn remaining(&self) -> usize {
+        let (Self::Fire(durability) | Self::Hit(durability)) = self;
+        *durability
+    }
+}
+
+/// Durability a weapon is repaired up to, not counting its affixes.
+///
+/// Recorded from the [`WeaponDurability`] the weapon is spawned with.
+#[derive(Component)]
+pub struct MaxDurability(pub usize);
+
+fn record_max_durability(
+    trigger: On<Add, WeaponDurability>,
+    mut commands: Commands,
+    durability: Query<&WeaponDurability>,
+) {
+    if let Ok(durability) = durability.get(trigger.entity) {
+        commands
+            .entity(trigger.entity)
+            .insert_if_new(MaxDurability(durability.remaining()));
+    }
+}
+
+/// Decrements the [`WeaponDurability::Hit`] of the weapon when this attack lands.
+#[derive(Component)]
+pub struct DecrementDurabilityOnHit(Entity);
+
+/// The entity wielding the weapon that triggered this attack, or that threw it.
+///
+/// Carried onto [`HitEvent::wielder`], since attacks such as projectiles are not parented to it.
+#[derive(Component)]
+pub struct Wielder(pub Entity);
+
+/// Rounds loaded into a ranged weapon.
+///
+/// A weapon with an empty magazine will not fire and instead begins
+/// reloading from its [`ReserveAmmo`].
+#[derive(Component)]
+pub struct Magazine {
+    pub capacity: usize,
+    pub rounds: usize,
+}
+
+impl Magazine {
+    /// A full magazine.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            rounds: capacity,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.rounds >= self.capacity
+    }
+}
+
+/// Rounds carried for a ranged weapon that are not yet loaded into its [`Magazine`].
+#[derive(Component)]
+pub struct ReserveAmmo(pub usize);
+
+/// Reloads never draw from [`ReserveAmmo`].
+///
+/// This marker can live anywhere above the weapon.
+#[derive(Default, Component)]
+pub struct InfiniteAmmo;
+
+/// Time it takes to refill a weapon's [`Magazine`].
+#[derive(Clone, Component)]
+pub struct ReloadDuration(Timer);
+
+impl ReloadDuration {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+/// The weapon is reloading and can not be triggered.
+#[derive(Component)]
+pub struct Reloading;
+
+/// Loose rounds that are collected into the [`ReserveAmmo`] of a held weapon
+/// when within `radius`.
+#[derive(Component)]
+#[require(
+    Transform,
+    Sprite::from_color(Color::srgb(1.0, 0.85, 0.2), Vec2::splat(8.0)),
+    Name::new("Ammo")
+)]
+pub struct AmmoPickup {
+    pub rounds: usize,
+    pub radius: f32,
+}
+
+impl AmmoPickup {
+    pub fn new(rounds: usize) -> Self {
+        Self {
+            rounds,
+            radius: 30.0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Component)]
+pub struct WeaponKnockback(pub f32);
+
+impl Default for WeaponKnockback {
+    fn default() -> Self {
+        Self(200.0)
+    }
+}
+
+pub fn weapon_knockback(hit: On<HitEvent>, mut commands: Commands) {
+    // Damage over time and finishers do not push their target.
+    if hit.knockback == Vec2::ZERO {
+        return;
+    }
+    let start = hit.knockback;
+    let end = Vec2::ZERO;
+    let animation = commands
+        .animation()
+        .insert(tween(
+            Duration::from_secs_f32(0.2),
+            EaseKind::Linear,
+            hit.target.into_target().with(acceleration(start, end)),
+        ))
+        .id();
+    // TODO: despawn knockback tweens
+    commands.entity(hit.target).add_child(animation);
+}
+
+/// Weapon pickup radius.
+#[derive(Component)]
+pub struct WeaponPickup(pub f32);
+
+/// Despawns with its wielder instead of dropping as a [`WeaponPickup`].
+#[derive(Component)]
+pub struct NoDrop;
+
+impl Default for WeaponPickup {
+    fn default() -> Self {
+        WeaponPickup(50.0)
+    }
+}
+
+/// Takes a [`WeaponPickup`] off the floor and parents it to `wielder`.
+pub fn equip<'a>(
+    commands: &'a mut Commands,
+    weapon: Entity,
+    wielder: Entity,
+) -> EntityCommands<'a> {
+    let mut entity = commands.entity(weapon);
+    entity
+        .remove::<(WeaponPickup, RigidBody)>()
+        .insert(ChildOf(wielder));
+    entity
+}
+
+/// The seperation between the root transform and the middle of the
+/// weapon transform.
+#[derive(Component)]
+pub struct WeaponReach(pub f32);
+
+fn weapon_reach(
+    mut weapons: Query<
+        (&mut Transform, &WeaponReach),
+        (With<ChildOf>, Or<(Changed<WeaponReach>, Added<ChildOf>)>),
+    >,
+) {
+    for (mut transform, reach) in weapons.iter_mut() {
+        transform.translation.x = 0.0;
+        transform.translation.y = reach.0;
+        transform.rotation = Quat::default();
+    }
+}
+
+#[derive(Component)]
+struct WeaponSprite(&'static str);
+
+fn weapon_sprite(
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    weapon_sprites: Query<(Entity, &WeaponSprite), Added<WeaponSprite>>,
+) {
+    for (entity, sprite_path) in weapon_sprites.iter() {
+        commands
+            .entity(entity)
+            .insert(Sprite::from_image(server.load(sprite_path.0)));
+    }
+}
+
+#[derive(Clone, Component)]
+pub struct AttackDuration(Timer);
+
+impl AttackDuration {
+    pub fn from_seconds(duration: f32) -> Self {
+        Self(Timer::from_seconds(duration, TimerMode::Once))
+    }
+}
+
+fn attack_duration(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut durations: Query<(Entity, &mut AttackDuration), Without<Weapon>>,
+) {
+    for (entity, mut duration) in durations.iter_mut() {
+        duration.0.tick(time.delta());
+        if duration.0.is_finished() {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+#[derive(Clone, Component)]
+pub struct AttackCooldown(Timer);
+
+impl AttackCooldown {
+    pub fn from_seconds(duration: f32) -> Self {
+        let mut timer = Timer::from_seconds(duration, TimerMode::Once);
+        timer.finish();
+        Self(timer)
+    }
+}
+
+fn attack_cooldown(time: Res<Time>, mut cooldowns: Query<&mut AttackCooldown, With<Weapon>>) {
+    for mut duration in cooldowns.iter_mut() {
+        if !duration.0.is_finished() {
+            duration.0.tick(time.delta());
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct AttackDamage(pub Damage);
+
+/// Triggers `count` attacks spaced evenly across an arc of `angle` radians.
+#[derive(Clone, Copy, Component)]
+pub struct Spread {
+    pub count: usize,
+    pub angle: f32,
+}
+
+impl Default for Spread {
+    fn default() -> Self {
+        Self {
+            count: 1,
+            angle: 0.0,
+        }
+    }
+}
+
+impl Spread {
+    /// Attack directions for every attack in the spread, centered on `direction`.
+    pub fn directions(self, direction: Vec2) -> impl Iterator<Item = Vec2> {
+        let count = self.count.max(1);
+        let angle = self.angle;
+        (0..count).map(move |i| {
+            let offset = if count > 1 {
+                -angle / 2.0 + angle * i as f32 / (count - 1) as f32
+            } else {
+                0.0
+            };
+            Vec2::from_angle(offset).rotate(direction)
+        })
+    }
+}
+
+/// Attacks are recycled through the [`Projectile`] pool instead of being spawned.
+#[derive(Default, Component)]
+pub struct PooledAttacks;
+
+/// Registers an [`AttackHandlerSystem`] for a weapon.
+#[derive(Component)]
+#[component(on_insert = Self::insert)]
+pub struct AttackHandler(TypeId, Option<Box<dyn FnOnce(&mut World) + Send + Sync>>);
+
+impl AttackHandler {
+    pub fn new<T, M>(f: T) -> Self
+    where
+        T: IntoSystem<In<TriggerWeaponData>, Result, M> + Send + Sync + 'static,
+    {
+        let id = TypeId::of::<T>();
+        Self(
+            id,
+            Some(Box::new(move |world: &mut World| {
+                world.resource_scope::<AttackHandlerRegistry, ()>(move |world, mut registry| {
+                    registry
+                        .0
+                        .entry(id)
+                        .or_insert_with(|| world.register_system(f));
+                })
+            })),
+        )
+    }
+
+    pub fn melee() -> Self {
+        Self::new(melee::default_melee_handler)
+    }
+
+    pub fn bullet() -> Self {
+        Self::new(default_bullet_handler)
+    }
+
+    pub fn spin() -> Self {
+        Self::new(melee::spin_melee_handler)
+    }
+
+    /// Whether the handler is the system `f`.
+    pub fn is<T: 'static>(&self, _f: T) -> bool {
+        self.0 == TypeId::of::<T>()
+    }
+
+    /// Whether the handler fires projectiles instead of swinging.
+    pub fn is_ranged(&self) -> bool {
+        self.is(default_bullet_handler)
+    }
+
+    fn insert(mut world: DeferredWorld, ctx: HookContext) {
+        world.commands().queue(move |world: &mut World| {
+            let mut handler = world.get_mut::<Self>(ctx.entity).unwrap();
+            if let Some(command) = handler.1.take() {
+                command(world);
+            }
+        });
+    }
+}
+
+#[derive(Default, Resource)]
+struct AttackHandlerRegistry(HashMap<TypeId, SystemId<In<TriggerWeaponData>, Result>>);
+
+#[derive(Clone, EntityEvent)]
+pub struct TriggerWeapon {
+    entity: Entity,
+    friendly: bool,
+    /// Fraction of a full [`ChargeAttack`] charge.
+    charge: Option<f32>,
+}
+
+impl TriggerWeapon {
+    pub fn friendly(entity: Entity) -> Self {
+        Self {
+            entity,
+            friendly: true,
+            charge: None,
+        }
+    }
+
+    pub fn enemy(entity: Entity) -> Self {
+        Self {
+            entity,
+            friendly: false,
+            charge: None,
+        }
+    }
+
+    /// Fires the weapon's [`ChargeAttack`] instead of its normal attack.
+    pub fn with_charge(mut self, charge: f32) -> Self {
+        self.charge = Some(charge);
+        self
+    }
+}
+
+fn propogate_trigger_weapon(
+    trigger: On<TriggerWeapon>,
+    mut commands: Commands,
+    children: Query<&Children, Without<Weapon>>,
+    weapons: Query<Entity, With<Weapon>>,
+) {
+    if let Ok(children) = children.get(trigger.entity) {
+        let mut iter = weapons.iter_many(children);
+        if let Some(weapon_entity) = iter.next() {
+            commands
+                .entity(weapon_entity)
+                .trigger(|entity| TriggerWeapon {
+                    entity,
+                    friendly: trigger.friendly,
+                    charge: trigger.charge,
+                });
+            if iter.next().is_some() {
+                error!("entity contains multiple weapons");
+            }
+        }
+    }
+}
+
+pub struct TriggerWeaponData {
+    attack_vector: Vec2,
+    attack: Entity,
+    weapon: Entity,
+    /// Fraction of a full charge if this is a [`ChargeAttack`].
+    pub charge: Option<f32>,
+    /// Index of the [`ComboStep`] if the weapon has a [`Combo`].
+    pub combo_step: Option<usize>,
+}
+
+fn trigger_weapon(
+    trigger: On<TriggerWeapon>,
+    registry: ResMut<AttackHandlerRegistry>,
+    mut commands: Commands,
+    mut pool: EntityPool,
+    mut weapons: Query<
+        (
+            &mut AttackCooldown,
+            Option<&mut WeaponDurability>,
+            Option<&mut Magazine>,
+            &AttackDamage,
+            &WeaponKnockback,
+            (&BitProducer, &BitSource),
+            &AttackHandler,
+            Option<&ChargeAttack>,
+            Option<&Spread>,
+            (Option<&OnHitEffects>, Option<&MultiHit>, Option<&Affixes>),
+            (
+                Option<&CritChance>,
+                Option<&CritMultiplier>,
+                Option<&DamageVariance>,
+            ),
+            Has<PooledAttacks>,
+            Has<Reloading>,
+        ),
+        With<Weapon>,
+    >,
+    wielders: AncestorQuery<
+        (Option<&CritChance>, Option<&CritMultiplier>),
+        Or<(With<CritChance>, With<CritMultiplier>)>,
+    >,
+    combos: Query<(&Combo, Option<&ComboState>)>,
+    transforms: Query<&GlobalTransform>,
+    apply_durability: AncestorQuery<&ApplyWeaponDurability>,
+    factions: AncestorQuery<&Faction>,
+    parents: Query<&ChildOf>,
+    mut rng: Single<&mut WyRand, With<GlobalRng>>,
+) -> Result {
+    if let Ok((
+        mut cooldown,
+        durability,
+        magazine,
+        damage,
+        knockback,
+        (bit_producer, bit_source),
+        handler,
+        charge_attack,
+        spread,
+        (effects, multi_hit, affixes),
+        (crit_chance, crit_multiplier, variance),
+        pooled,
+        reloading,
+    )) = weapons.get_mut(trigger.entity)
+    {
+        if !cooldown.0.is_finished() || reloading {
+            return Ok(());
+        }
+        if let Some(mut magazine) = magazine {
+            if magazine.is_empty() {
+                commands.entity(trigger.entity).trigger(ReloadWeapon::new);
+                return Ok(());
+            }
+            magazine.rounds -= 1;
+        }
+        cooldown.0.reset();
+
+        let weapon_transform = transforms.get(trigger.entity)?;
+        let rotation = weapon_transform.rotation().to_euler(EulerRot::ZYX).0;
+        let attack_vector = Vec2::Y.rotate(Vec2::from_angle(rotation));
+
+        let apply_durability = apply_durability.get(trigger.entity).is_ok();
+        let decrement_on_hit =
+            apply_durability && matches!(durability.as_deref(), Some(WeaponDurability::Hit(_)));
+
+        let faction = factions.get(trigger.entity).ok().copied();
+        let wielder = parents.get(trigger.entity).ok().map(ChildOf::parent);
+
+        let damage = damage.0.0 * affixes.map_or(1.0, Affixes::damage);
+        let bit_producer = bit_producer.0 + affixes.map_or(0, Affixes::bits);
+        let charge = trigger.charge.zip(charge_attack);
+        let (id, mut damage, knockback, bits) = match charge {
+            Some((charge, attack)) => (
+                *registry.0.get(&attack.handler.0).unwrap(),
+                damage * ChargeAttack::scale(attack.damage, charge),
+                knockback.0 * ChargeAttack::scale(attack.knockback, charge),
+                (bit_producer as f32 * ChargeAttack::scale(attack.bits, charge)).round() as usize,
+            ),
+            None => (
+                *registry.0.get(&handler.0).unwrap(),
+                damage,
+                knockback.0,
+                bit_producer,
+            ),
+        };
+        let charge = charge.map(|(charge, _)| charge);
+
+        let combo_step = combos.get(trigger.entity).ok().and_then(|(combo, state)| {
+            let step = combo.advance(&mut commands, trigger.entity, state)?;
+            damage *= combo.steps[step].damage;
+            Some(step)
+        });
+
+        let (wielder_chance, wielder_multiplier) = wielders.get(trigger.entity).unwrap_or_default();
+        let crit_chance = crit_chance.map_or(0.0, |chance| chance.0)
+            + wielder_chance.map_or(0.0, |chance| chance.0);
+        let crit_multiplier = crit_multiplier
+            .or(wielder_multiplier)
+            .copied()
+            .unwrap_or(CritMultiplier::DEFAULT);
+
+        let spread = spread.copied().unwrap_or_default();
+        let spread = affixes.map_or(spread, |affixes| affixes.spread(spread));
+        let effects = effects
+            .into_iter()
+            .flat_map(|effects| effects.0.iter().copied())
+            .chain(affixes.into_iter().flat_map(Affixes::effects))
+            .collect::<Vec<_>>();
+
+        for attack_vector in spread.directions(attack_vector) {
+            let variance = variance.map_or(1.0, |variance| {
+                rng.random_range(1.0 - variance.0..=1.0 + variance.0)
+            });
+            let crit = rng.random_bool(crit_chance.clamp(0.0, 1.0) as f64);
+            let damage = damage * variance * if crit { crit_multiplier.0 } else { 1.0 };
+
+            let bundle = (
+                Damage(damage),
+                WeaponKnockback(knockback),
+                BitProducer(bits),
+                *bit_source,
+            );
+            let mut entity = if pooled {
+                pool.spawn::<Projectile>(bundle)
+            } else {
+                commands.spawn(bundle)
+            };
+            if trigger.friendly {
+                entity.insert(FriendlyHitbox);
+            } else {
+                entity.insert(EnemyHitbox);
+            }
+            if decrement_on_hit {
+                entity.insert(DecrementDurabilityOnHit(trigger.entity));
+            }
+            if !effects.is_empty() {
+                entity.insert(OnHitEffects(effects.clone()));
+            }
+            if let Some(multi_hit) = multi_hit {
+                entity.insert(*multi_hit);
+            }
+            if let Some(faction) = faction {
+                entity.insert(faction);
+            }
+            if let Some(wielder) = wielder {
+                entity.insert(Wielder(wielder));
+            }
+            if crit {
+                entity.insert(Critical);
+            }
+
+            let input = TriggerWeaponData {
+                attack_vector,
+                attack: entity.id(),
+                weapon: trigger.entity,
+                charge,
+                combo_step,
+            };
+            commands.queue_handled(
+                move |world: &mut World| world.run_system_with(id, input),
+                bevy::ecs::error::warn,
+            );
+        }
+
+        if apply_durability
+            && let Some(durability) = durability
+            && let WeaponDurability::Fire(durability) = durability.into_inner()
+        {
+            *durability = durability.saturating_sub(1);
+            if *durability == 0 {
+                commands.entity(trigger.entity).despawn();
+            }
+        }
+    }
+    Ok(())
+}
+
+#[derive(Clone, EntityEvent)]
+pub struct ReloadWeapon {
+    entity: Entity,
+}
+
+impl ReloadWeapon {
+    pub fn new(entity: Entity) -> Self {
+        Self { entity }
+    }
+}
+
+fn propogate_reload_weapon(
+    trigger: On<ReloadWeapon>,
+    mut commands: Commands,
+    children: Query<&Children, Without<Weapon>>,
+    weapons: Query<Entity, With<Weapon>>,
+) {
+    if let Ok(children) = children.get(trigger.entity)
+        && let Some(weapon_entity) = weapons.iter_many(children).next()
+    {
+        commands.entity(weapon_entity).trigger(ReloadWeapon::new);
+    }
+}
+
+fn reload_weapon(
+    trigger: On<ReloadWeapon>,
+    mut commands: Commands,
+    mut weapons: Query<
+        (
+            &Magazine,
+            Option<&ReserveAmmo>,
+            &mut ReloadDuration,
+            Has<Reloading>,
+        ),
+        With<Weapon>,
+    >,
+    infinite_ammo: AncestorQuery<&InfiniteAmmo>,
+) {
+    if let Ok((magazine, reserve, mut duration, reloading)) = weapons.get_mut(trigger.entity) {
+        let has_reserve = infinite_ammo.get(trigger.entity).is_ok()
+            || reserve.is_some_and(|reserve| reserve.0 > 0);
+        if reloading || magazine.is_full() || !has_reserve {
+            return;
+        }
+
+        duration.0.reset();
+        commands.entity(trigger.entity).insert(Reloading);
+    }
+}
+
+fn reload(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut weapons: Query<
+        (
+            Entity,
+            &mut ReloadDuration,
+            &mut Magazine,
+            Option<&mut ReserveAmmo>,
+        ),
+        (With<Weapon>, With<Reloading>),
+    >,
+    infinite_ammo: AncestorQuery<&InfiniteAmmo>,
+) {
+    for (entity, mut duration, mut magazine, reserve) in weapons.iter_mut() {
+        duration.0.tick(time.delta());
+        if !duration.0.is_finished() {
+            continue;
+        }
+
+        let missing = magazine.capacity.saturating_sub(magazine.rounds);
+        let loaded = if infinite_ammo.get(entity).is_ok() {
+            missing
+        } else if let Some(mut reserve) = reserve {
+            let loaded = missing.min(reserve.0);
+            reserve.0 -= loaded;
+            loaded
+        } else {
+            0
+        };
+        magazine.rounds += loaded;
+        commands.entity(entity).remove::<Reloading>();
+    }
+}
+
+fn default_bullet_handler(
+    data: In<TriggerWeaponData>,
+    mut commands: Commands,
+    server: Res<AssetServer>,
+    bullet_weapons: Query<(
+        &GlobalTransform,
+        &ProjectileSpeed,
+        &ProjectileSize,
+        Option<&ProjectileSprite>,
+        Option<&ProjectileDrag>,
+    )>,
+    projectile_modifiers: Query<(
+        Option<&Pierce>,
+        Option<&Ricochet>,
+        Option<&Homing>,
+        Option<&Lifetime>,
+    )>,
+    layers: Query<&CollisionLayers>,
+) -> Result {
+    let (transform, speed, size, sprite, drag) = bullet_weapons.get(data.weapon)?;
+    let (pierce, ricochet, homing, lifetime) = projectile_modifiers.get(data.weapon)?;
+    let translation = transform.translation().xy();
+
+    // Projectiles are stopped by walls.
+    let mut layers = *layers.get(data.attack)?;
+    layers.filters |= Layer::Wall.to_bits();
+
+    let sprite = match sprite {
+        Some(sprite) => Sprite {
+            image: server.load(sprite.0),
+            custom_size: Some(Vec2::splat(size.0)),
+            ..Default::default()
+        },
+        None => Sprite::from_color(Color::WHITE, Vec2::splat(size.0)),
+    };
+
+    let mut entity = commands.entity(data.attack);
+    entity
+        .insert((
+            Transform::from_translation(translation.extend(0.0)),
+            sprite,
+            Collider::circle(size.0 / 2.0),
+            LinearDamping(drag.map(|drag| drag.0).unwrap_or_default()),
+            LinearVelocity(data.attack_vector * speed.0),
+            layers,
+            DestroyOnImpact,
+            Projectile,
+        ))
+        // `Sensor` causing warnings for some reason. We don't need it
+        // since the collision layers exlude the collisions with the
+        // player and enemies.
+        .remove::<Sensor>();
+    if let Some(pierce) = pierce {
+        entity.insert(*pierce);
+    }
+    if let Some(ricochet) = ricochet {
+        entity.insert(*ricochet);
+    }
+    if let Some(homing) = homing {
+        entity.insert(*homing);
+    }
+    if let Some(lifetime) = lifetime {
+        entity.insert(lifetime.clone());
+    }
+    Ok(())
+}
+
+/// Despawns a hitbox on [`HitEvent`].
+///
+/// A hitbox with [`Pierce`] survives until it runs out of pierces.
+#[derive(Component)]
+pub struct DestroyOnImpact;
+
+pub(crate) fn handle_attack(
+    mut hit: On<HitEvent>,
+    mut commands: Commands,
+    mut pool: EntityPool,
+    mut attacks: Query<(
+        Has<DestroyOnImpact>,
+        Option<&mut Pierce>,
+        Option<&DecrementDurabilityOnHit>,
+    )>,
+    mut durability: Query<&mut WeaponDurability>,
+) {
+    if let Some(attacker) = hit.attacker.take() {
+        let (destroy, pierce, decrement) = attacks.get_mut(attacker).unwrap();
+        if destroy {
+            match pierce {
+                Some(mut pierce) if pierce.0 > 0 => pierce.0 -= 1,
+                _ => pool.despawn(attacker),
+            }
+        }
+        if let Some(DecrementDurabilityOnHit(weapon)) = decrement
+            && let Ok(WeaponDurability::Hit(durability)) =
+                durability.get_mut(*weapon).as_deref_mut()
+        {
+            *durability = durability.saturating_sub(1);
+            if *durability == 0 {
+                commands.entity(*weapon).despawn();
+            }
+        }
+    }
+}
+
+#[derive(EntityEvent)]
+#[entity_event(auto_propagate)]
+pub struct HitEvent {
+    #[event_target]
+    pub target: Entity,
+    /// The hitbox that hit `target`.
+    ///
+    /// `attacker` is taken and despawned in [`handle_attack`].
+    pub attacker: Option<Entity>,
+    /// The entity wielding the weapon behind the hit, which is kept as the event propagates.
+    pub wielder: Option<Entity>,
+    pub damage: f32,
+    /// Observe the root with [`weapon_knockback`] to apply.
+    pub knockback: Vec2,
+    /// Observe the root with [`produce_bits`](crate::bits::produce_bits) to apply.
+    pub bits: usize,
+    pub source: BitSource,
+    pub target_translation: Vec2,
+    pub attacker_translation: Vec2,
+    /// The attack was a [`Critical`] hit.
+    pub crit: bool,
+}
+
+/// Roots that have been struck by an attack, along with the time of their last hit.
+///
+/// Roots are the nearest [`RigidBody`] above a hurtbox, so an attack can only
+/// hit an entity once no matter how many hurtboxes it has, or how many times
+/// it enters the hitbox. See [`MultiHit`] to hit repeatedly.
+#[derive(Default, Component)]
+pub struct AlreadyHit(EntityHashMap<Duration>);
+
+impl AlreadyHit {
+    /// Registers a hit on `root`, returning `false` if it can not be hit yet.
+    pub fn register(&mut self, root: Entity, now: Duration, multi_hit: Option<&MultiHit>) -> bool {
+        match self.0.get(&root) {
+            Some(last) if multi_hit.is_none_or(|multi_hit| now - *last < multi_hit.interval) => {
+                false
+            }
+            _ => {
+                self.0.insert(root, now);
+                true
+            }
+        }
+    }
+}
+
+/// Allows an attack to hit the same root again every `interval` while it
+/// remains in contact.
+///
+/// Placed on a weapon, every attack it triggers can hit repeatedly.
+#[derive(Clone, Copy, Component)]
+pub struct MultiHit {
+    pub interval: Duration,
+}
+
+impl MultiHit {
+    pub fn from_seconds(interval: f32) -> Self {
+        Self {
+            interval: Duration::from_secs_f32(interval),
+        }
+    }
+}
+
+fn hit_event(
+    start: On<CollisionStart>,
+    mut hits: Hits,
+    time: Res<Time>,
+    target: Query<&Hurtbox>,
+    mut attacker: Query<
+        (
+            &BitProducer,
+            &WeaponKnockback,
+            &Damage,
+            &mut AlreadyHit,
+            Option<&MultiHit>,
+        ),
+        With<Hitbox>,
+    >,
+) -> Result {
+    if target.get(start.collider1).is_ok()
+        && let Ok((bit_producer, knockback, damage, mut already_hit, multi_hit)) =
+            attacker.get_mut(start.collider2)
+    {
+        let target = start.collider1;
+        let attacker = start.collider2;
+
+        if !hits.hostile(target, attacker)
+            || !already_hit.register(hits.root(target), time.elapsed(), multi_hit)
+        {
+            return Ok(());
+        }
+
+        hits.trigger(target, attacker, (bit_producer, knockback, damage));
+    }
+    Ok(())
+}
+
+/// Repeats hits for [`MultiHit`] attacks that are still touching a hurtbox.
+fn multi_hit(
+    mut hits: Hits,
+    time: Res<Time>,
+    collisions: Collisions,
+    mut attackers: Query<
+        (
+            Entity,
+            &BitProducer,
+            &WeaponKnockback,
+            &Damage,
+            &mut AlreadyHit,
+            &MultiHit,
+        ),
+        With<Hitbox>,
+    >,
+    targets: Query<(), With<Hurtbox>>,
+) {
+    for (attacker, bit_producer, knockback, damage, mut already_hit, multi_hit) in
+        attackers.iter_mut()
+    {
+        for contact_pair in collisions.collisions_with(attacker) {
+            if !contact_pair.is_touching() {
+                continue;
+            }
+
+            let target = if contact_pair.collider1 == attacker {
+                contact_pair.collider2
+            } else {
+                contact_pair.collider1
+            };
+            if !targets.contains(target) {
+                continue;
+            }
+
+            if hits.hostile(target, attacker)
+                && already_hit.register(hits.root(target), time.elapsed(), Some(multi_hit))
+            {
+                hits.trigger(target, attacker, (bit_producer, knockback, damage));
+            }
+        }
+    }
+}
+
+/// Resolves a hitbox touching a hurtbox into a [`HitEvent`].
+#[derive(SystemParam)]
+struct Hits<'w, 's> {
+    commands: Commands<'w, 's>,
+    roots: AncestorQuery<'w, 's, Entity, With<RigidBody>>,
+    transforms: Query<'w, 's, &'static GlobalTransform>,
+    guards: Query<'w, 's, (&'static Block, &'static Blocking, &'static GlobalTransform)>,
+    effects: Query<'w, 's, &'static OnHitEffects>,
+    affixes: Query<'w, 's, &'static Affixes>,
+    crits: Query<'w, 's, (), With<Critical>>,
+    weak_points: Query<'w, 's, &'static WeakPoint>,
+    armor: Query<'w, 's, &'static Armor>,
+    sources: Query<'w, 's, &'static BitSource>,
+    wielders: Query<'w, 's, &'static Wielder>,
+    factions: AncestorQuery<'w, 's, &'static Faction>,
+    relations: Res<'w, FactionRelations>,
+}
+
+impl Hits<'_, '_> {
+    /// The rigid body that owns `target`, used to deduplicate hits.
+    fn root(&self, target: Entity) -> Entity {
+        self.roots.get_inclusive(target).unwrap_or(target)
+    }
+
+    /// Whether the [`Faction`] of `attacker` is hostile toward that of `target`.
+    ///
+    /// Without a faction on either side, only the collision layers decide.
+    fn hostile(&self, target: Entity, attacker: Entity) -> bool {
+        match (
+            self.factions.get_inclusive(target),
+            self.factions.get_inclusive(attacker),
+        ) {
+            (Ok(target), Ok(attacker)) => self.relations.is_hostile(*attacker, *target),
+            _ => true,
+        }
+    }
+
+    fn trigger(
+        &mut self,
+        target: Entity,
+        attacker: Entity,
+        (bit_producer, knockback, damage): (&BitProducer, &WeaponKnockback, &Damage),
+    ) {
+        let target_transform = self.transforms.get(target).unwrap();
+        let attacker_transform = self.transforms.get(attacker).unwrap();
+        let target_translation = target_transform.translation().xy();
+        let attacker_translation = attacker_transform.translation().xy();
+        let diff = target_translation - attacker_translation;
+
+        // Thrown weapons hit with their own components, layered with their affixes.
+        let affixes = self.affixes.get(attacker).ok();
+        let crit = self.crits.contains(attacker);
+        let mut damage = damage.0 * affixes.map_or(1.0, Affixes::damage);
+        let mut knockback = diff.normalize_or(Vec2::Y) * knockback.0;
+        let mut bits = bit_producer.0 + affixes.map_or(0, Affixes::bits);
+        if crit {
+            knockback *= CRIT_KNOCKBACK;
+            bits *= CRIT_BITS;
+        }
+        if let Ok(weak_point) = self.weak_points.get(target) {
+            damage *= weak_point.0;
+        }
+
+        let defender = self.root(target);
+        if let Ok(armor) = self.armor.get(defender) {
+            damage *= 1.0 - armor.0;
+        }
+        let guard = self
+            .guards
+            .get(defender)
+            .map(|(block, blocking, transform)| {
+                block.guard(blocking, transform, attacker_translation)
+            })
+            .unwrap_or(Guard::Open);
+        match guard {
+            Guard::Open => {
+                let effects = self
+                    .effects
+                    .get(attacker)
+                    .into_iter()
+                    .flat_map(|effects| effects.0.iter().copied())
+                    .chain(affixes.into_iter().flat_map(Affixes::effects));
+                for effect in effects {
+                    self.commands
+                        .entity(defender)
+                        .trigger(|target| InflictStatus { target, effect });
+                }
+            }
+            Guard::Parried => {
+                self.commands
+                    .entity(defender)
+                    .trigger(|defender| Parried { defender, attacker });
+                return;
+            }
+            Guard::Blocked(reduction) => {
+                damage *= 1.0 - reduction;
+                knockback *= 1.0 - reduction;
+                self.commands.entity(defender).trigger(Blocked);
+            }
+        }
+
+        self.commands.entity(target).trigger(|target| HitEvent {
+            target,
+            attacker: Some(attacker),
+            wielder: self.wielders.get(attacker).ok().map(|wielder| wielder.0),
+            damage,
+            knockback,
+            bits,
+            source: self.sources.get(attacker).copied().unwrap_or_default(),
+            target_translation,
+            attacker_translation,
+            crit,
+        });
+    }
+}
+
+fn finish_throw(mut commands: Commands, weapons: Query<(Entity, &LinearVelocity), With<Weapon>>) {
+    for (entity, velocity) in weapons.iter() {
+        if velocity.0.length_squared() < 10.0 * 10.0 {
+            commands
+                .entity(entity)
+                .remove::<(
+                    Hitbox,
+                    FriendlyHitbox,
+                    EnemyHitbox,
+                    AlreadyHit,
+                    Faction,
+                    Wielder,
+                )>()
+                .insert((
+                    ColliderDisabled,
+                    WeaponPickup::default(),
+                    CollisionLayers::NONE,
+                ));
+        }
+    }
+}
+
+fn remove_weapon_rigidbody(
+    mut commands: Commands,
+    weapons: Query<(Entity, &LinearVelocity), With<Weapon>>,
+) {
+    for (entity, velocity) in weapons.iter() {
+        if velocity.0.length_squared() < f32::EPSILON * f32::EPSILON {
+            commands.entity(entity).remove::<RigidBody>();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn already_hit_dedupes_roots() {
+        let mut world = World::new();
+        let (a, b) = (world.spawn_empty().id(), world.spawn_empty().id());
+        let mut already_hit = AlreadyHit::default();
+
+        assert!(already_hit.register(a, Duration::ZERO, None));
+        assert!(!already_hit.register(a, Duration::from_secs(10), None));
+        assert!(already_hit.register(b, Duration::from_secs(10), None));
+    }
+
+    #[test]
+    fn multi_hit_repeats_every_interval() {
+        let mut world = World::new();
+        let root = world.spawn_empty().id();
+        let multi_hit = MultiHit::from_seconds(0.5);
+        let mut already_hit = AlreadyHit::default();
+        let mut register =
+            |secs: f32| already_hit.register(root, Duration::from_secs_f32(secs), Some(&multi_hit));
+
+        assert!(register(0.0));
+        assert!(!register(0.25));
+        assert!(register(0.5));
+        assert!(!register(0.75));
+        assert!(register(1.0));
+    }
+}